@@ -1,12 +1,25 @@
 my_data = {
     text = "my text"
 }
+-- nested style getters return copies, so each level has to be assigned back
+function set_hovered_fill(ctx, color)
+    local style = ctx:style();
+    local visuals = style.visuals;
+    local widgets = visuals.widgets;
+    local hovered = widgets.hovered;
+    hovered.bg_fill = color;
+    widgets.hovered = hovered;
+    visuals.widgets = widgets;
+    style.visuals = visuals;
+    ctx:set_style(style);
+end
 -- a function to run inside the window
 function window_ui(ui)
     ui:label(my_data.text);
     ui:text_edit_singleline(my_data);
     if ui:button("cute button"):clicked() then
         print("cute button pressed.");
+        set_hovered_fill(ui:ctx(), egui.color32.dark_red);
     end
 end
 -- will be called every frame with egui Context as arg
@@ -1,14 +1,35 @@
+use std::{cell::Cell, collections::BTreeMap, rc::Rc, sync::Arc};
+
 use egui::{
-    epaint::Shadow,
+    collapsing_header::{CollapsingResponse, CollapsingState},
+    ecolor::Hsva,
+    epaint::{
+        CircleShape, CubicBezierShape, Mesh, PathShape, QuadraticBezierShape, RectShape, Shadow,
+        Vertex, WHITE_UV,
+    },
     load::SizedTexture,
-    style::{Spacing, WidgetVisuals},
-    Align, Align2, Area, CentralPanel, Color32, Context, Direction, Frame, Id, LayerId, Layout,
-    Margin, Order, PointerButton, Pos2, Rect, RichText, Rounding, Sense, SidePanel, Stroke, Style,
-    TextStyle, TextureHandle, TopBottomPanel, Ui, Vec2, WidgetText, Window,
+    menu,
+    output::OutputEvent,
+    popup_above_or_below_widget, popup_below_widget,
+    scroll_area::ScrollAreaOutput,
+    show_tooltip_at, show_tooltip_at_pointer, show_tooltip_for, show_tooltip_text,
+    style::{Interaction, ScrollStyle, Selection, Spacing, WidgetVisuals, Widgets},
+    text::{CCursor, FontData, FontDefinitions, Fonts, LayoutJob, TextFormat, TextWrapping},
+    text_edit::TextEditOutput,
+    util::IdTypeMap,
+    was_tooltip_open_last_frame, AboveOrBelow, Align, Align2, Area, Button, CentralPanel, Checkbox,
+    CollapsingHeader, Color32, ColorImage, ComboBox, Context, CursorIcon, Direction, DragValue,
+    Event, FontFamily, FontId, FontSelection, Frame, Galley, Grid, Hyperlink, Id, Image,
+    ImageButton, InputState, Key, KeyboardShortcut, Label, LayerId, Layout, Link, Margin, Memory,
+    Modifiers, OpenUrl, Order, Painter, PlatformOutput, PointerButton, PointerState, Pos2,
+    ProgressBar, RadioButton, Rect, Rgba, RichText, Rounding, ScrollArea, SelectableLabel, Sense,
+    Separator, Shape, SidePanel, Slider, SliderOrientation, Spinner, Stroke, Style, TextEdit,
+    TextStyle, TextureFilter, TextureHandle, TextureOptions, TextureWrapMode, TopBottomPanel, Ui,
+    Vec2, Visuals, WidgetText, Window,
 };
 use mlua::{
-    AnyUserData, Function, Lua, MultiValue, Result, Table, UserDataFields, UserDataMethods,
-    UserDataRef, UserDataRefMut, UserDataRegistry, Value, Vector,
+    AnyUserData, Function, Lua, MetaMethod, MultiValue, RegistryKey, Result, Table, UserDataFields,
+    UserDataMethods, UserDataRef, UserDataRefMut, UserDataRegistry, Value, Vector,
 };
 
 trait LuaHelperTrait: Sized {
@@ -20,33 +41,81 @@ trait LuaHelperTrait: Sized {
 pub fn register_egui_bindings(lua: &Lua) -> mlua::Result<()> {
     let et = lua.create_table()?;
     let egui_table = &et;
+    AboveOrBelow::add_to_lua(lua, egui_table)?;
     Align::add_to_lua(lua, egui_table)?;
     Align2::add_to_lua(lua, egui_table)?;
     Color32::add_to_lua(lua, egui_table)?;
+    CursorIcon::add_to_lua(lua, egui_table)?;
     Direction::add_to_lua(lua, egui_table)?;
+    FontFamily::add_to_lua(lua, egui_table)?;
+    FontId::add_to_lua(lua, egui_table)?;
+    Hsva::add_to_lua(lua, egui_table)?;
     Id::add_to_lua(lua, egui_table)?;
+    Key::add_to_lua(lua, egui_table)?;
     Margin::add_to_lua(lua, egui_table)?;
+    Modifiers::add_to_lua(lua, egui_table)?;
     PointerButton::add_to_lua(lua, egui_table)?;
     Pos2::add_to_lua(lua, egui_table)?;
     Rect::add_to_lua(lua, egui_table)?;
+    Rgba::add_to_lua(lua, egui_table)?;
     RichText::add_to_lua(lua, egui_table)?;
     Rounding::add_to_lua(lua, egui_table)?;
     Sense::add_to_lua(lua, egui_table)?;
+    SliderOrientation::add_to_lua(lua, egui_table)?;
     Stroke::add_to_lua(lua, egui_table)?;
+    TextFormat::add_to_lua(lua, egui_table)?;
     TextStyle::add_to_lua(lua, egui_table)?;
+    TextureOptions::add_to_lua(lua, egui_table)?;
+    TextWrapping::add_to_lua(lua, egui_table)?;
     Vec2::add_to_lua(lua, egui_table)?;
     WidgetText::add_to_lua(lua, egui_table)?;
 
     add_area(lua, egui_table)?;
+    add_button(lua, egui_table)?;
+    add_checkbox(lua, egui_table)?;
+    add_collapsing_header(lua, egui_table)?;
+    add_collapsing_state(lua, egui_table)?;
+    add_color_image(lua, egui_table)?;
+    add_combo_box(lua, egui_table)?;
     add_context(lua, egui_table)?;
+    add_drag_value(lua, egui_table)?;
     add_frame(lua, egui_table)?;
+    add_font_definitions(lua, egui_table)?;
+    add_fonts(lua)?;
+    add_galley(lua)?;
+    add_grid(lua, egui_table)?;
+    add_hyperlink(lua, egui_table)?;
+    add_image(lua, egui_table)?;
+    add_image_button(lua, egui_table)?;
+    add_id_type_map(lua)?;
+    add_input_state(lua)?;
     add_layer_id(lua, egui_table)?;
+    add_label(lua, egui_table)?;
     add_layout(lua, egui_table)?;
+    add_layout_job(lua, egui_table)?;
+    add_link(lua, egui_table)?;
+    add_memory(lua)?;
+    add_menu(lua, egui_table)?;
+    add_painter(lua, egui_table)?;
+    add_platform_output(lua)?;
+    add_popup(lua, egui_table)?;
+    add_progress_bar(lua, egui_table)?;
+    add_radio_button(lua, egui_table)?;
     add_response(lua)?;
     add_shadow(lua, egui_table)?;
+    add_scroll_area(lua, egui_table)?;
+    add_selectable_label(lua, egui_table)?;
+    add_separator(lua, egui_table)?;
+    add_shape(lua, egui_table)?;
+    add_slider(lua, egui_table)?;
     add_spacing(lua, egui_table)?;
+    add_spinner(lua, egui_table)?;
     add_style(lua, egui_table)?;
+    add_text_edit(lua, egui_table)?;
+    add_texture_handle(lua)?;
+    add_tooltip(lua, egui_table)?;
     add_ui(lua, egui_table)?;
+    add_visuals(lua, egui_table)?;
     add_widget_visuals(lua, egui_table)?;
     add_window(lua, egui_table)?;
     add_central_panel(lua, egui_table)?;
@@ -67,6 +136,126 @@ fn add_context(lua: &Lua, _: &Table) -> mlua::Result<()> {
             this.request_repaint_after(std::time::Duration::from_secs_f64(duration));
             Ok(())
         });
+        reg.add_method("input", |lua, this, add_contents: Function| {
+            this.input(|reader| {
+                lua.scope(|scope| {
+                    let reader = scope.create_any_userdata_ref(reader)?;
+                    let result: Result<MultiValue> = add_contents.call(reader);
+                    result
+                })
+            })
+        });
+        reg.add_method("memory", |lua, this, add_contents: Function| {
+            this.memory(|reader| {
+                lua.scope(|scope| {
+                    let reader = scope.create_any_userdata_ref(reader)?;
+                    let result: Result<MultiValue> = add_contents.call(reader);
+                    result
+                })
+            })
+        });
+        reg.add_method("memory_mut", |lua, this, add_contents: Function| {
+            this.memory_mut(|reader| {
+                lua.scope(|scope| {
+                    let reader = scope.create_any_userdata_ref_mut(reader)?;
+                    let result: Result<MultiValue> = add_contents.call(reader);
+                    result
+                })
+            })
+        });
+        reg.add_method("style", |lua, this, ()| {
+            lua.create_any_userdata(this.style().as_ref().clone())
+        });
+        reg.add_method(
+            "set_fonts",
+            |_, this, font_definitions: UserDataRef<FontDefinitions>| {
+                check_font_definitions(&font_definitions)?;
+                this.set_fonts(font_definitions.clone());
+                Ok(())
+            },
+        );
+        reg.add_method("set_style", |_, this, style: UserDataRef<Style>| {
+            this.set_style(style.clone());
+            Ok(())
+        });
+        reg.add_method("set_visuals", |_, this, visuals: UserDataRef<Visuals>| {
+            this.set_visuals(visuals.clone());
+            Ok(())
+        });
+        reg.add_method(
+            "pixels_per_point",
+            |_, this, ()| Ok(this.pixels_per_point()),
+        );
+        reg.add_method("set_pixels_per_point", |_, this, pixels_per_point: f32| {
+            this.set_pixels_per_point(pixels_per_point);
+            Ok(())
+        });
+        reg.add_method("screen_rect", |lua, this, ()| {
+            Rect::to_lua(this.screen_rect(), lua)
+        });
+        reg.add_method("available_rect", |lua, this, ()| {
+            Rect::to_lua(this.available_rect(), lua)
+        });
+        reg.add_method("used_rect", |lua, this, ()| {
+            Rect::to_lua(this.used_rect(), lua)
+        });
+        reg.add_method("is_pointer_over_area", |_, this, ()| {
+            Ok(this.is_pointer_over_area())
+        });
+        reg.add_method("wants_pointer_input", |_, this, ()| {
+            Ok(this.wants_pointer_input())
+        });
+        reg.add_method("wants_keyboard_input", |_, this, ()| {
+            Ok(this.wants_keyboard_input())
+        });
+        reg.add_method("frame_nr", |_, this, ()| Ok(this.frame_nr()));
+        reg.add_method(
+            "layer_painter",
+            |lua, this, layer_id: UserDataRef<LayerId>| {
+                lua.create_any_userdata(this.layer_painter(*layer_id))
+            },
+        );
+        reg.add_method("debug_painter", |lua, this, ()| {
+            lua.create_any_userdata(this.debug_painter())
+        });
+        reg.add_method(
+            "format_shortcut",
+            |_, this, (modifiers, key): (Value, Value)| {
+                Ok(this.format_shortcut(&KeyboardShortcut::new(
+                    Modifiers::from_lua(modifiers)?,
+                    Key::from_lua(key)?,
+                )))
+            },
+        );
+        reg.add_method("copy_text", |_, this, text: String| {
+            this.copy_text(text);
+            Ok(())
+        });
+        reg.add_method(
+            "open_url",
+            |_, this, (url, new_tab): (String, Option<bool>)| {
+                this.open_url(if new_tab.unwrap_or_default() {
+                    OpenUrl::new_tab(url)
+                } else {
+                    OpenUrl::same_tab(url)
+                });
+                Ok(())
+            },
+        );
+        reg.add_method(
+            "load_texture",
+            |lua, this, (name, image, options): (String, UserDataRef<ColorImage>, Value)| {
+                lua.create_any_userdata(this.load_texture(
+                    name,
+                    image.clone(),
+                    TextureOptions::from_lua(options)?,
+                ))
+            },
+        );
+        reg.add_method("set_cursor_icon", |_, this, cursor_icon: Value| {
+            this.set_cursor_icon(CursorIcon::from_lua(cursor_icon)?);
+            Ok(())
+        });
     })?;
     Ok(())
 }
@@ -217,445 +406,1055 @@ fn add_layout(lua: &Lua, egui_table: &Table) -> mlua::Result<()> {
     egui_table.set("layout", layout)?;
     Ok(())
 }
-fn add_spacing(lua: &Lua, _egui_table: &Table) -> mlua::Result<()> {
-    lua.register_userdata_type(|reg: &mut UserDataRegistry<Spacing>| {
-        reg.add_field_method_get("item_spacing", |lua, this| {
-            Vec2::to_lua(this.item_spacing, lua)
+
+fn add_memory(lua: &Lua) -> mlua::Result<()> {
+    lua.register_userdata_type(|reg: &mut UserDataRegistry<Memory>| {
+        reg.add_method("focus", |lua, this, ()| {
+            this.focus().map(|id| Id::to_lua(id, lua)).transpose()
         });
-        reg.add_field_method_get("window_margin", |lua, this| {
-            Margin::to_lua(this.window_margin, lua)
+        reg.add_method("has_focus", |_, this, id: Value| {
+            Ok(this.has_focus(Id::from_lua(id)?))
         });
-        reg.add_field_method_get("button_padding", |lua, this| {
-            Vec2::to_lua(this.button_padding, lua)
+        reg.add_method_mut("request_focus", |_, this, id: Value| {
+            this.request_focus(Id::from_lua(id)?);
+            Ok(())
         });
-        reg.add_field_method_get("menu_margin", |lua, this| {
-            Margin::to_lua(this.menu_margin, lua)
+        reg.add_method_mut("surrender_focus", |_, this, id: Value| {
+            this.surrender_focus(Id::from_lua(id)?);
+            Ok(())
         });
-        reg.add_field_method_get("indent", |_, this| Ok(this.indent));
-
-        reg.add_field_method_get("interact_size", |lua, this| {
-            Vec2::to_lua(this.interact_size, lua)
+        reg.add_method("is_popup_open", |_, this, id: Value| {
+            Ok(this.is_popup_open(Id::from_lua(id)?))
         });
-        reg.add_field_method_get("slider_width", |_, this| Ok(this.slider_width));
-        reg.add_field_method_get("combo_width", |_, this| Ok(this.combo_width));
-        reg.add_field_method_get("text_edit_width", |_, this| Ok(this.text_edit_width));
-        reg.add_field_method_get("icon_width", |_, this| Ok(this.icon_width));
-        reg.add_field_method_get("icon_width_inner", |_, this| Ok(this.icon_width_inner));
-        reg.add_field_method_get("icon_spacing", |_, this| Ok(this.icon_spacing));
-        reg.add_field_method_get("tooltip_width", |_, this| Ok(this.tooltip_width));
-        reg.add_field_method_get("indent_ends_with_horizontal_line", |_, this| {
-            Ok(this.indent_ends_with_horizontal_line)
+        reg.add_method("any_popup_open", |_, this, ()| Ok(this.any_popup_open()));
+        reg.add_method_mut("open_popup", |_, this, id: Value| {
+            this.open_popup(Id::from_lua(id)?);
+            Ok(())
         });
-        reg.add_field_method_get("combo_height", |_, this| Ok(this.combo_height));
-        // reg.add_field_method_get("scroll_bar_width", |_, this| Ok(this.scroll_bar_width));
-        // reg.add_field_method_get("scroll_handle_min_length", |_, this| {
-        //     Ok(this.scroll_handle_min_length)
-        // });
-        // reg.add_field_method_get("scroll_bar_inner_margin", |_, this| {
-        //     Ok(this.scroll_bar_inner_margin)
-        // });
-        // reg.add_field_method_get("scroll_bar_outer_margin", |_, this| {
-        //     Ok(this.scroll_bar_outer_margin)
-        // });
+        reg.add_method_mut("toggle_popup", |_, this, id: Value| {
+            this.toggle_popup(Id::from_lua(id)?);
+            Ok(())
+        });
+        reg.add_method_mut("close_popup", |_, this, ()| {
+            this.close_popup();
+            Ok(())
+        });
+        reg.add_method_mut("reset_areas", |_, this, ()| {
+            this.reset_areas();
+            Ok(())
+        });
+    })
+}
+/// Temp data can be any lua value, so we just keep it alive in the lua registry.
+#[derive(Clone)]
+struct LuaRegistryValue(Arc<RegistryKey>);
 
-        // reg.add_field_method_set("weak_bg_fill", |_, this, value: Value| {
-        //     this.weak_bg_fill = Color32::from_lua(value)?;
-        //     Ok(())
-        // });
-        // reg.add_field_method_set("bg_stroke", |_, this, value: Value| {
-        //     this.bg_stroke = Stroke::from_lua(value)?;
-        //     Ok(())
-        // });
-        // reg.add_field_method_set("rounding", |_, this, value: Value| {
-        //     this.rounding = Rounding::from_lua(value)?;
-        //     Ok(())
-        // });
-        // reg.add_field_method_set("fg_stroke", |_, this, value: Value| {
-        //     this.fg_stroke = Stroke::from_lua(value)?;
-        //     Ok(())
-        // });
-        // reg.add_field_method_set(
-        //     "expansion",
-        //     |_, this, value: f32| Ok(this.expansion = value),
-        // );
-    })?;
-    Ok(())
+/// Persisted data must be serializable, so only booleans, numbers and strings are allowed.
+fn remove_persisted(this: &mut IdTypeMap, id: Id) {
+    this.remove::<bool>(id);
+    this.remove::<i64>(id);
+    this.remove::<f64>(id);
+    this.remove::<String>(id);
 }
-fn add_response(lua: &Lua) -> mlua::Result<()> {
-    lua.register_userdata_type(|reg: &mut UserDataRegistry<egui::Response>| {
-        reg.add_method("changed", |_, this, ()| Ok(this.changed()));
-        reg.add_method("clicked", |_, this, ()| Ok(this.clicked()));
-        reg.add_method("clicked_by", |_, this, value: Value| {
-            Ok(this.clicked_by(PointerButton::from_lua(value)?))
+fn add_id_type_map(lua: &Lua) -> mlua::Result<()> {
+    lua.register_userdata_type(|reg: &mut UserDataRegistry<IdTypeMap>| {
+        reg.add_method("get_temp", |lua, this, id: Value| {
+            match this.get_temp::<LuaRegistryValue>(Id::from_lua(id)?) {
+                Some(value) => lua.registry_value(&value.0),
+                None => Ok(Value::Nil),
+            }
         });
-        reg.add_method("clicked_elsewhere", |_, this, ()| {
-            Ok(this.clicked_elsewhere())
+        reg.add_method_mut("insert_temp", |lua, this, (id, value): (Value, Value)| {
+            let value = LuaRegistryValue(Arc::new(lua.create_registry_value(value)?));
+            this.insert_temp(Id::from_lua(id)?, value);
+            lua.expire_registry_values();
+            Ok(())
         });
-        // reg.add_method("context_menu", |_, this, ()| Ok(this.changed()));
-        reg.add_method("double_clicked", |_, this, ()| Ok(this.double_clicked()));
-        reg.add_method("double_clicked_by", |_, this, value: Value| {
-            Ok(this.double_clicked_by(PointerButton::from_lua(value)?))
+        reg.add_method_mut("get_persisted", |lua, this, id: Value| {
+            let id = Id::from_lua(id)?;
+            Ok(if let Some(b) = this.get_persisted::<bool>(id) {
+                Value::Boolean(b)
+            } else if let Some(i) = this.get_persisted::<i64>(id) {
+                Value::Integer(i as _)
+            } else if let Some(n) = this.get_persisted::<f64>(id) {
+                Value::Number(n)
+            } else if let Some(s) = this.get_persisted::<String>(id) {
+                Value::String(lua.create_string(s)?)
+            } else {
+                Value::Nil
+            })
         });
-        reg.add_method("drag_delta", |lua, this, ()| {
-            Ok(Vec2::to_lua(this.drag_delta(), lua))
+        reg.add_method_mut(
+            "insert_persisted",
+            |_, this, (id, value): (Value, Value)| {
+                let id = Id::from_lua(id)?;
+                remove_persisted(this, id);
+                match value {
+                    Value::Nil => {}
+                    Value::Boolean(b) => this.insert_persisted(id, b),
+                    Value::Integer(i) => this.insert_persisted(id, i as i64),
+                    Value::Number(n) => this.insert_persisted(id, n),
+                    Value::String(s) => this.insert_persisted(id, s.to_str()?.to_string()),
+                    _ => {
+                        return Err(mlua::Error::RuntimeError(
+                            "only booleans, numbers and strings can be persisted".to_owned(),
+                        ))
+                    }
+                }
+                Ok(())
+            },
+        );
+        reg.add_method_mut("remove", |lua, this, id: Value| {
+            let id = Id::from_lua(id)?;
+            this.remove::<LuaRegistryValue>(id);
+            remove_persisted(this, id);
+            lua.expire_registry_values();
+            Ok(())
         });
-        reg.add_method("drag_released", |_, this, ()| Ok(this.drag_released()));
-        reg.add_method("drag_released_by", |_, this, value: Value| {
-            Ok(this.drag_released_by(PointerButton::from_lua(value)?))
+    })
+}
+fn event_to_lua<'lua>(lua: &'lua Lua, event: &Event) -> Result<Value<'lua>> {
+    let table = lua.create_table()?;
+    match event {
+        Event::Copy => table.set("kind", "copy")?,
+        Event::Cut => table.set("kind", "cut")?,
+        Event::Paste(text) => {
+            table.set("kind", "paste")?;
+            table.set("text", text.as_str())?;
+        }
+        Event::Text(text) => {
+            table.set("kind", "text")?;
+            table.set("text", text.as_str())?;
+        }
+        Event::Key {
+            key,
+            pressed,
+            repeat,
+            modifiers,
+            ..
+        } => {
+            table.set("kind", "key")?;
+            table.set("key", Key::to_lua(*key, lua)?)?;
+            table.set("pressed", *pressed)?;
+            table.set("repeat", *repeat)?;
+            table.set("modifiers", Modifiers::to_lua(*modifiers, lua)?)?;
+        }
+        Event::PointerMoved(pos) => {
+            table.set("kind", "pointer_moved")?;
+            table.set("pos", Pos2::to_lua(*pos, lua)?)?;
+        }
+        Event::PointerButton {
+            pos,
+            button,
+            pressed,
+            modifiers,
+        } => {
+            table.set("kind", "pointer_button")?;
+            table.set("pos", Pos2::to_lua(*pos, lua)?)?;
+            table.set("button", PointerButton::to_lua(*button, lua)?)?;
+            table.set("pressed", *pressed)?;
+            table.set("modifiers", Modifiers::to_lua(*modifiers, lua)?)?;
+        }
+        Event::PointerGone => table.set("kind", "pointer_gone")?,
+        Event::Scroll(delta) => {
+            table.set("kind", "scroll")?;
+            table.set("delta", Vec2::to_lua(*delta, lua)?)?;
+        }
+        Event::Zoom(factor) => {
+            table.set("kind", "zoom")?;
+            table.set("factor", *factor)?;
+        }
+        _ => table.set("kind", "other")?,
+    }
+    Ok(Value::Table(table))
+}
+fn add_input_state(lua: &Lua) -> mlua::Result<()> {
+    lua.register_userdata_type(|reg: &mut UserDataRegistry<InputState>| {
+        reg.add_method("key_pressed", |_, this, key: Value| {
+            Ok(this.key_pressed(Key::from_lua(key)?))
         });
-        reg.add_method("drag_started", |_, this, ()| Ok(this.drag_started()));
-        reg.add_method("drag_started_by", |_, this, value: Value| {
-            Ok(this.drag_started_by(PointerButton::from_lua(value)?))
+        reg.add_method("key_down", |_, this, key: Value| {
+            Ok(this.key_down(Key::from_lua(key)?))
         });
-        reg.add_method("dragged", |_, this, ()| Ok(this.dragged()));
-        reg.add_method("dragged_by", |_, this, value: Value| {
-            Ok(this.dragged_by(PointerButton::from_lua(value)?))
+        reg.add_method("key_released", |_, this, key: Value| {
+            Ok(this.key_released(Key::from_lua(key)?))
         });
-        reg.add_method("enabled", |_, this, ()| Ok(this.enabled()));
-        reg.add_method("gained_focus", |_, this, ()| Ok(this.gained_focus()));
-        reg.add_method("has_focus", |_, this, ()| Ok(this.has_focus()));
-        reg.add_method("highlight", |lua, this, ()| {
-            lua.create_any_userdata(this.clone().highlight())
+        reg.add_method("modifiers", |lua, this, ()| {
+            Modifiers::to_lua(this.modifiers, lua)
+        });
+        reg.add_method("pointer", |lua, this, ()| {
+            lua.create_any_userdata(this.pointer.clone())
+        });
+        reg.add_method("scroll_delta", |lua, this, ()| {
+            Vec2::to_lua(this.smooth_scroll_delta, lua)
+        });
+        reg.add_method("raw_scroll_delta", |lua, this, ()| {
+            Vec2::to_lua(this.raw_scroll_delta, lua)
         });
+        reg.add_method("zoom_delta", |_, this, ()| Ok(this.zoom_delta()));
+        reg.add_method("time", |_, this, ()| Ok(this.time));
+        reg.add_method("unstable_dt", |_, this, ()| Ok(this.unstable_dt));
+        reg.add_method("screen_rect", |lua, this, ()| {
+            Rect::to_lua(this.screen_rect(), lua)
+        });
+        reg.add_method("events", |lua, this, ()| {
+            let events = lua.create_table()?;
+            for event in this.events.iter() {
+                events.push(event_to_lua(lua, event)?)?;
+            }
+            Ok(events)
+        });
+        reg.add_method_mut(
+            "consume_key",
+            |_, this, (modifiers, key): (Value, Value)| {
+                Ok(this.consume_key(Modifiers::from_lua(modifiers)?, Key::from_lua(key)?))
+            },
+        );
+        reg.add_method_mut(
+            "consume_shortcut",
+            |_, this, (modifiers, key): (Value, Value)| {
+                Ok(this.consume_shortcut(&KeyboardShortcut::new(
+                    Modifiers::from_lua(modifiers)?,
+                    Key::from_lua(key)?,
+                )))
+            },
+        );
+    })?;
+    lua.register_userdata_type(|reg: &mut UserDataRegistry<PointerState>| {
         reg.add_method("hover_pos", |lua, this, ()| {
             Ok(this.hover_pos().and_then(|p| Pos2::to_lua(p, lua).ok()))
         });
-        reg.add_method("hovered", |_, this, ()| Ok(this.hovered()));
-    })
+        reg.add_method("interact_pos", |lua, this, ()| {
+            Ok(this.interact_pos().and_then(|p| Pos2::to_lua(p, lua).ok()))
+        });
+        reg.add_method("press_origin", |lua, this, ()| {
+            Ok(this.press_origin().and_then(|p| Pos2::to_lua(p, lua).ok()))
+        });
+        reg.add_method("button_down", |_, this, button: Value| {
+            Ok(this.button_down(PointerButton::from_lua(button)?))
+        });
+        reg.add_method("button_pressed", |_, this, button: Value| {
+            Ok(this.button_pressed(PointerButton::from_lua(button)?))
+        });
+        reg.add_method("button_released", |_, this, button: Value| {
+            Ok(this.button_released(PointerButton::from_lua(button)?))
+        });
+        reg.add_method("button_clicked", |_, this, button: Value| {
+            Ok(this.button_clicked(PointerButton::from_lua(button)?))
+        });
+        reg.add_method("any_down", |_, this, ()| Ok(this.any_down()));
+        reg.add_method("delta", |lua, this, ()| Vec2::to_lua(this.delta(), lua));
+        reg.add_method("velocity", |lua, this, ()| {
+            Vec2::to_lua(this.velocity(), lua)
+        });
+        reg.add_method("is_moving", |_, this, ()| Ok(this.is_moving()));
+    })?;
+    Ok(())
 }
 
-fn add_ui(lua: &Lua, _egui_table: &Table) -> mlua::Result<()> {
-    lua.register_userdata_type(|reg: &mut UserDataRegistry<Ui>| {
-        reg.add_method_mut(
-            "add_enabled_ui",
-            |lua, this, (enabled, add_contents): (bool, Function)| {
-                let ir = this.add_enabled_ui(enabled, |ui| {
-                    lua.scope(|scope| {
-                        let ui = scope.create_any_userdata_ref_mut(ui)?;
-                        let result: Result<MultiValue> = add_contents.call(ui);
-                        result
-                    })
-                });
-                let r = lua.create_any_userdata(ir.response)?;
-                let mut i = ir.inner?;
-                i.push_front(Value::UserData(r));
-                Ok(i)
-            },
-        );
+fn output_event_to_lua<'lua>(lua: &'lua Lua, event: &OutputEvent) -> Result<Value<'lua>> {
+    let table = lua.create_table()?;
+    table.set(
+        "kind",
+        match event {
+            OutputEvent::Clicked(_) => "clicked",
+            OutputEvent::DoubleClicked(_) => "double_clicked",
+            OutputEvent::TripleClicked(_) => "triple_clicked",
+            OutputEvent::FocusGained(_) => "focus_gained",
+            OutputEvent::TextSelectionChanged(_) => "text_selection_changed",
+            OutputEvent::ValueChanged(_) => "value_changed",
+        },
+    )?;
+    let info = event.widget_info();
+    table.set("enabled", info.enabled)?;
+    table.set("label", info.label.clone())?;
+    table.set("current_text_value", info.current_text_value.clone())?;
+    table.set("prev_text_value", info.prev_text_value.clone())?;
+    table.set("selected", info.selected)?;
+    table.set("value", info.value)?;
+    Ok(Value::Table(table))
+}
+fn add_platform_output(lua: &Lua) -> mlua::Result<()> {
+    lua.register_userdata_type(|reg: &mut UserDataRegistry<PlatformOutput>| {
+        reg.add_field_method_get("cursor_icon", |lua, this| {
+            CursorIcon::to_lua(this.cursor_icon, lua)
+        });
+        reg.add_field_method_get("copied_text", |_, this| Ok(this.copied_text.clone()));
+        reg.add_field_method_get("open_url", |lua, this| {
+            let Some(open_url) = this.open_url.as_ref() else {
+                return Ok(Value::Nil);
+            };
+            let table = lua.create_table()?;
+            table.set("url", open_url.url.clone())?;
+            table.set("new_tab", open_url.new_tab)?;
+            Ok(Value::Table(table))
+        });
+        reg.add_field_method_get("mutable_text_under_cursor", |_, this| {
+            Ok(this.mutable_text_under_cursor)
+        });
+        reg.add_field_method_get("events", |lua, this| {
+            let events = lua.create_table()?;
+            for event in this.events.iter() {
+                events.push(output_event_to_lua(lua, event)?)?;
+            }
+            Ok(events)
+        });
 
-        reg.add_method_mut("add_space", |_, this, amount: f32| {
-            this.add_space(amount);
+        reg.add_field_method_set("cursor_icon", |_, this, value: Value| {
+            this.cursor_icon = CursorIcon::from_lua(value)?;
             Ok(())
         });
-        reg.add_method_mut(
-            "add_visible_ui",
-            |lua, this, (visible, add_contents): (bool, Function)| {
-                let ir = this.add_visible_ui(visible, |ui| {
-                    lua.scope(|scope| {
-                        let ui = scope.create_any_userdata_ref_mut(ui)?;
-                        let result: Result<MultiValue> = add_contents.call(ui);
-                        result
+        reg.add_field_method_set("copied_text", |_, this, value: String| {
+            this.copied_text = value;
+            Ok(())
+        });
+        // either a url string (opened in the same tab) or a table `{url = "..", new_tab = true}`
+        reg.add_field_method_set("open_url", |_, this, value: Value| {
+            this.open_url = match value {
+                Value::Nil => None,
+                Value::String(url) => Some(OpenUrl::same_tab(url.to_str()?)),
+                Value::Table(t) => Some(OpenUrl {
+                    url: t.get("url")?,
+                    new_tab: t.get::<_, Option<bool>>("new_tab")?.unwrap_or_default(),
+                }),
+                _ => {
+                    return Err(mlua::Error::FromLuaConversionError {
+                        from: "luavalue",
+                        to: "openurl",
+                        message: Some("expected nil, a url string or a table".to_string()),
                     })
-                });
-                let r = lua.create_any_userdata(ir.response)?;
-                let mut i = ir.inner?;
-                i.push_front(Value::UserData(r));
-                Ok(i)
+                }
+            };
+            Ok(())
+        });
+    })
+}
+fn add_painter(lua: &Lua, _egui_table: &Table) -> mlua::Result<()> {
+    lua.register_userdata_type(|reg: &mut UserDataRegistry<Painter>| {
+        reg.add_method("clip_rect", |lua, this, ()| Rect::to_lua(this.clip_rect(), lua));
+        reg.add_method("with_clip_rect", |lua, this, rect: Value| {
+            lua.create_any_userdata(this.with_clip_rect(Rect::from_lua(rect)?))
+        });
+        reg.add_method("with_layer_id", |lua, this, layer_id: UserDataRef<LayerId>| {
+            lua.create_any_userdata(this.clone().with_layer_id(*layer_id))
+        });
+        reg.add_method("add", |_, this, shape: UserDataRef<Shape>| {
+            this.add(shape.clone());
+            Ok(())
+        });
+        reg.add_method("extend", |_, this, shapes: Table| {
+            this.extend(shapes_from_lua(shapes)?);
+            Ok(())
+        });
+        reg.add_method(
+            "line_segment",
+            |_, this, (start, end, stroke): (Value, Value, Value)| {
+                this.line_segment(
+                    [Pos2::from_lua(start)?, Pos2::from_lua(end)?],
+                    Stroke::from_lua(stroke)?,
+                );
+                Ok(())
             },
         );
-        reg.add_method_mut(
-            "allocate_at_least",
-            |lua, this, (desired_size, sense): (Value, Value)| {
-                let (rect, resp) = this.allocate_at_least(
-                    LuaHelperTrait::from_lua(desired_size)?,
-                    LuaHelperTrait::from_lua(sense)?,
+        reg.add_method(
+            "rect_filled",
+            |_, this, (rect, rounding, fill_color): (Value, Value, Value)| {
+                this.rect_filled(
+                    Rect::from_lua(rect)?,
+                    Rounding::from_lua(rounding)?,
+                    Color32::from_lua(fill_color)?,
                 );
-
-                Ok((Rect::to_lua(rect, lua)?, lua.create_any_userdata(resp)?))
+                Ok(())
             },
         );
-        reg.add_method_mut(
-            "allocate_exact_size",
-            |lua, this, (desired_size, sense): (Value, Value)| {
-                let (rect, resp) = this.allocate_exact_size(
-                    LuaHelperTrait::from_lua(desired_size)?,
-                    LuaHelperTrait::from_lua(sense)?,
+        reg.add_method(
+            "rect_stroke",
+            |_, this, (rect, rounding, stroke): (Value, Value, Value)| {
+                this.rect_stroke(
+                    Rect::from_lua(rect)?,
+                    Rounding::from_lua(rounding)?,
+                    Stroke::from_lua(stroke)?,
                 );
-
-                Ok((Rect::to_lua(rect, lua)?, lua.create_any_userdata(resp)?))
+                Ok(())
             },
         );
-        reg.add_method_mut(
-            "allocate_rect",
-            |lua, this, (rect, sense): (Value, Value)| {
-                lua.create_any_userdata(this.allocate_rect(
-                    LuaHelperTrait::from_lua(rect)?,
-                    LuaHelperTrait::from_lua(sense)?,
-                ))
+        reg.add_method(
+            "circle_filled",
+            |_, this, (center, radius, fill_color): (Value, f32, Value)| {
+                this.circle_filled(Pos2::from_lua(center)?, radius, Color32::from_lua(fill_color)?);
+                Ok(())
             },
         );
-        reg.add_method_mut(
-            "allocate_response",
-            |lua, this, (desired_size, sense): (Value, Value)| {
-                lua.create_any_userdata(this.allocate_response(
-                    LuaHelperTrait::from_lua(desired_size)?,
-                    LuaHelperTrait::from_lua(sense)?,
-                ))
+        reg.add_method(
+            "circle_stroke",
+            |_, this, (center, radius, stroke): (Value, f32, Value)| {
+                this.circle_stroke(Pos2::from_lua(center)?, radius, Stroke::from_lua(stroke)?);
+                Ok(())
             },
         );
-
-        reg.add_method_mut("allocate_space", |lua, this, desired_size: Value| {
-            let (id, rect) = this.allocate_space(LuaHelperTrait::from_lua(desired_size)?);
-            Ok((lua.create_any_userdata(id)?, Rect::to_lua(rect, lua)?))
-        });
-        reg.add_method_mut(
-            "allocate_ui",
-            |lua, this, (desired_size, add_contents): (Value, Function)| {
-                let ir = this.allocate_ui(Vec2::from_lua(desired_size)?, |ui| {
-                    lua.scope(|scope| {
-                        let ui = scope.create_any_userdata_ref_mut(ui)?;
-                        let result: Result<MultiValue> = add_contents.call(ui);
-                        result
-                    })
-                });
-                let r = lua.create_any_userdata(ir.response)?;
-                let mut i = ir.inner?;
-                i.push_front(Value::UserData(r));
-                Ok(i)
+        reg.add_method(
+            "text",
+            |lua, this, (pos, anchor, text, font_id, text_color): (Value, Value, String, Value, Value)| {
+                let font_id = this.ctx().fonts(|fonts| font_id_from_lua(fonts, font_id))?;
+                let rect = this.text(
+                    Pos2::from_lua(pos)?,
+                    Align2::from_lua(anchor)?,
+                    text,
+                    font_id,
+                    Color32::from_lua(text_color)?,
+                );
+                Rect::to_lua(rect, lua)
             },
         );
-        reg.add_method_mut(
-            "allocate_ui_at_rect",
-            |lua, this, (max_rect, add_contents): (Value, Function)| {
-                let ir = this.allocate_ui_at_rect(Rect::from_lua(max_rect)?, |ui| {
-                    lua.scope(|scope| {
-                        let ui = scope.create_any_userdata_ref_mut(ui)?;
-                        let result: Result<MultiValue> = add_contents.call(ui);
-                        result
-                    })
-                });
-                let r = lua.create_any_userdata(ir.response)?;
-                let mut i = ir.inner?;
-                i.push_front(Value::UserData(r));
-                Ok(i)
+        reg.add_method(
+            "galley",
+            |_, this, (pos, galley, fallback_color): (Value, UserDataRef<Arc<Galley>>, Value)| {
+                this.galley(Pos2::from_lua(pos)?, galley.clone(), Color32::from_lua(fallback_color)?);
+                Ok(())
             },
         );
-        reg.add_method_mut(
-            "allocate_ui_with_layout",
-            |lua, this, (desired_size, layout, add_contents): (Value, UserDataRef<Layout>, Function)| {
-                let ir = this.allocate_ui_with_layout(Vec2::from_lua(desired_size)?, *layout,|ui| {
-                    lua.scope(|scope| {
-                        let ui = scope.create_any_userdata_ref_mut(ui)?;
-                        let result: Result<MultiValue> = add_contents.call(ui);
-                        result
-                    })
-                });
-                let r = lua.create_any_userdata(ir.response)?;
-                let mut i = ir.inner?;
-                i.push_front(Value::UserData(r));
-                Ok(i)
+        reg.add_method(
+            "layout",
+            |lua, this, (text, font_id, color, wrap_width): (String, Value, Value, f32)| {
+                let font_id = this.ctx().fonts(|fonts| font_id_from_lua(fonts, font_id))?;
+                lua.create_any_userdata(this.layout(
+                    text,
+                    font_id,
+                    Color32::from_lua(color)?,
+                    wrap_width,
+                ))
             },
         );
-        reg.add_method("auto_id_with", |lua, this, value: Value| {
-            lua.create_any_userdata(match value {
-              Value::Boolean(b) => {
-                this.auto_id_with(b)
-                },
-                Value::Integer(b) => this.auto_id_with(b),
-                Value::String(b) => this.auto_id_with(b),
-                _ => return Err(mlua::Error::external("value type cannot be hashed to get new id"))
-            })
-        });
-        reg.add_method("available_height", |_, this, ()|Ok(this.available_height()));
-        reg.add_method("available_rect_before_wrap", |lua, this, ()| Rect::to_lua(this.available_rect_before_wrap(), lua));
-        reg.add_method("avaialble_size", |lua, this, ()| Vec2::to_lua(this.available_size(), lua));
-        reg.add_method("avaialble_size_before_wrap", |lua, this, ()| Vec2::to_lua(this.available_size_before_wrap(), lua));
-        reg.add_method("available_width", |_, this, ()|Ok(this.available_width()));
-        reg.add_method_mut("button", |lua, this, value: Value| {
-            lua.create_any_userdata(this.button(WidgetText::from_lua(value)?))
-        });
-        reg.add_method_mut(
-            "centered_and_justified",
-            |lua, this, add_contents: Function| {
-                let ir = this.centered_and_justified(|ui| {
-                    lua.scope(|scope| {
-                        let ui = scope.create_any_userdata_ref_mut(ui)?;
-                        let result: Result<MultiValue> = add_contents.call(ui);
-                        result
-                    })
-                });
-                let r = lua.create_any_userdata(ir.response)?;
-                let mut i = ir.inner?;
-                i.push_front(Value::UserData(r));
-                Ok(i)
+        reg.add_method(
+            "layout_no_wrap",
+            |lua, this, (text, font_id, color): (String, Value, Value)| {
+                let font_id = this.ctx().fonts(|fonts| font_id_from_lua(fonts, font_id))?;
+                lua.create_any_userdata(this.layout_no_wrap(
+                    text,
+                    font_id,
+                    Color32::from_lua(color)?,
+                ))
             },
         );
-        reg.add_method_mut("checkbox", |lua, this, value: Table| {
-            let mut b: bool = value.get("checked")?;
-            let result = lua.create_any_userdata(this.checkbox(&mut b, WidgetText::from_lua(value.get("text")?)?));
-            value.set("checked", b)?;
-            result
-        });
-        reg.add_method_mut("child_ui", |lua, this, (max_rect, layout): (Value, UserDataRef<Layout>)| {
-            let ui =  this.child_ui(LuaHelperTrait::from_lua(max_rect)?, *layout);
-            lua.create_any_userdata(ui)
-        });
-        // requires impl Hash for Value smh
-        // reg.add_method_mut("child_ui_with_id_source", |lua, this, (max_rect, layout): (Value, Value)| {
-        //     let ui =  this.child_ui(LuaHelperTrait::from_lua(max_rect)?, LuaHelperTrait::from_lua(layout)?);
-        //     lua.create_any_userdata(ui)
-        // });
-        reg.add_method("clip_rect", |lua, this, ()| {
-            Rect::to_lua(this.clip_rect(), lua)
+        reg.add_method("layout_job", |lua, this, job: UserDataRef<LayoutJob>| {
+            this.ctx()
+                .fonts(|fonts| check_layout_job(&fonts.families(), &job))?;
+            lua.create_any_userdata(this.layout_job(job.clone()))
         });
-        reg.add_method_mut("close_menu", |_, this, ()| {
-            this.close_menu();
+        reg.add_method(
+            "arrow",
+            |_, this, (origin, vec, stroke): (Value, Value, Value)| {
+                this.arrow(Pos2::from_lua(origin)?, Vec2::from_lua(vec)?, Stroke::from_lua(stroke)?);
+                Ok(())
+            },
+        );
+    })?;
+    Ok(())
+}
+/// epaint panics when it lays out text with a family that is not in the current `FontDefinitions`.
+fn check_font_family(families: &[FontFamily], family: &FontFamily) -> Result<()> {
+    if families.contains(family) {
+        Ok(())
+    } else {
+        Err(mlua::Error::RuntimeError(format!(
+            "font family {family} is not in the font definitions"
+        )))
+    }
+}
+/// Converts a `FontId` that is going to be laid out with `fonts`.
+fn font_id_from_lua(fonts: &Fonts, value: Value) -> Result<FontId> {
+    let font_id = FontId::from_lua(value)?;
+    check_font_family(&fonts.families(), &font_id.family)?;
+    Ok(font_id)
+}
+fn check_layout_job(families: &[FontFamily], job: &LayoutJob) -> Result<()> {
+    job.sections
+        .iter()
+        .try_for_each(|section| check_font_family(families, &section.format.font_id.family))
+}
+/// egui panics when it resolves a text style that is not in `Style::text_styles`.
+fn check_text_style(text_styles: &[TextStyle], text_style: &TextStyle) -> Result<()> {
+    if text_styles.contains(text_style) {
+        Ok(())
+    } else {
+        Err(mlua::Error::RuntimeError(format!(
+            "text style {text_style} is not in the style's text_styles"
+        )))
+    }
+}
+/// Most text is built without a `Context`, so the containers keep the font families
+/// of the context they were shown with in the lua app data, and script text is checked against them.
+/// Before any container was shown, egui's defaults are used.
+struct KnownFonts {
+    families: Vec<FontFamily>,
+    text_styles: Vec<TextStyle>,
+}
+impl Default for KnownFonts {
+    fn default() -> Self {
+        Self {
+            families: FontDefinitions::default().families.into_keys().collect(),
+            text_styles: Style::default().text_styles.into_keys().collect(),
+        }
+    }
+}
+fn remember_fonts(lua: &Lua, ctx: &Context) {
+    lua.set_app_data(KnownFonts {
+        families: ctx.fonts(|fonts| fonts.families()),
+        text_styles: ctx.style().text_styles.keys().cloned().collect(),
+    });
+}
+fn with_known_fonts<R>(lua: &Lua, f: impl FnOnce(&KnownFonts) -> R) -> R {
+    match lua.app_data_ref::<KnownFonts>() {
+        Some(known_fonts) => f(&known_fonts),
+        None => f(&KnownFonts::default()),
+    }
+}
+/// Converts a `FontId` that is laid out later, checking its family against the known fonts.
+fn known_font_id_from_lua(lua: &Lua, value: Value) -> Result<FontId> {
+    let font_id = FontId::from_lua(value)?;
+    with_known_fonts(lua, |known| {
+        check_font_family(&known.families, &font_id.family)
+    })?;
+    Ok(font_id)
+}
+/// Like `TextFormat::from_lua`, but the family of the font id is checked against the known fonts.
+fn text_format_from_lua(lua: &Lua, value: Value) -> Result<TextFormat> {
+    let format = TextFormat::from_lua(value)?;
+    with_known_fonts(lua, |known| {
+        check_font_family(&known.families, &format.font_id.family)
+    })?;
+    Ok(format)
+}
+/// Like `RichText::from_lua`, but the `family` and `text_style` of the table form are checked.
+fn rich_text_from_lua(lua: &Lua, value: Value) -> Result<RichText> {
+    if let Value::Table(t) = &value {
+        let family: Option<FontFamily> = match t.get::<_, Value>("family")? {
+            Value::Nil => None,
+            family => Some(FontFamily::from_lua(family)?),
+        };
+        let text_style: Option<TextStyle> = match t.get::<_, Value>("text_style")? {
+            Value::Nil => None,
+            text_style => Some(TextStyle::from_lua(text_style)?),
+        };
+        with_known_fonts(lua, |known| -> Result<()> {
+            if let Some(family) = &family {
+                check_font_family(&known.families, family)?;
+            }
+            if let Some(text_style) = &text_style {
+                check_text_style(&known.text_styles, text_style)?;
+            }
             Ok(())
+        })?;
+    }
+    RichText::from_lua(value)
+}
+/// Like `WidgetText::from_lua`, but the table form is checked by [`rich_text_from_lua`].
+fn widget_text_from_lua(lua: &Lua, value: Value) -> Result<WidgetText> {
+    match value {
+        Value::Table(_) => Ok(rich_text_from_lua(lua, value)?.into()),
+        _ => WidgetText::from_lua(value),
+    }
+}
+fn points_from_lua(points: Table) -> Result<Vec<Pos2>> {
+    points
+        .sequence_values::<Value>()
+        .map(|p| Pos2::from_lua(p?))
+        .collect()
+}
+fn shapes_from_lua(shapes: Table) -> Result<Vec<Shape>> {
+    shapes
+        .sequence_values::<UserDataRef<Shape>>()
+        .map(|s| Ok(s?.clone()))
+        .collect()
+}
+fn add_shape(lua: &Lua, egui_table: &Table) -> mlua::Result<()> {
+    lua.register_userdata_type(|reg: &mut UserDataRegistry<Shape>| {
+        reg.add_method("visual_bounding_rect", |lua, this, ()| {
+            Rect::to_lua(this.visual_bounding_rect(), lua)
         });
-        reg.add_method_mut("code", |lua, this, value: Value| {
-            lua.create_any_userdata(this.code(RichText::from_lua(value)?))
-        });
-        reg.add_method_mut("code_editor", |lua, this, value: Table| {
-            let mut b: String = value.get("text")?;
-            let result = lua.create_any_userdata(this.code_editor(&mut b));
-            value.set("text", b)?;
-            result
+        reg.add_method_mut("translate", |_, this, delta: Value| {
+            this.translate(Vec2::from_lua(delta)?);
+            Ok(())
         });
-        // reg.add_method_mut(
-        //     "collapsing",
-        //     |lua, this, (heading, add_contents): (Value, Function)| {
-        //         let result = lua.create_table()?;
-        //         let ir = this.collapsing( WidgetText::from_lua(heading)?,|ui| {
-        //             lua.scope(|scope| {
-        //                 let ui = scope.create_any_userdata_ref_mut(ui)?;
-        //                 let _result: Result<MultiValue> = add_contents.call(ui);
-        //                 // some lifetime error...
-        //                 Ok(())
-        //             })
-        //         });
-        //         result.set("header_response", lua.create_any_userdata(ir.header_response)?)?;
-        //         result.set("body_response", lua.create_any_userdata(ir.body_response)?)?;
-        //         result.set("body_returned", lua.create_any_userdata(ir.body_returned)?)?;
-        //         result.set("openness", lua.create_any_userdata(ir.openness)?)?;
-        //         Ok(Value::Table(result))
-        //     },
-        // );
-        reg.add_method_mut(
-            "columns",
-            |lua, this, (num, add_contents): (usize, Function)| {
-                let ir = this.columns( num, |cols| {
-                    lua.scope(|scope| {
-                        let cols: Vec<AnyUserData> = cols.into_iter().map(|ui| scope.create_any_userdata_ref_mut(ui)).collect::<Result<Vec<AnyUserData>>>()?;
-                        let result: Result<MultiValue> = add_contents.call(cols);
-                        result
-                    })
-                });
-                ir
-            },
-        );
-        reg.add_method(
-            "ctx",
-            |lua, this, ()| {
-                lua.create_any_userdata(this.ctx().clone())
+    })?;
+    let shape = lua.create_table()?;
+    shape.set(
+        "circle",
+        lua.create_function(
+            |lua, (center, radius, fill, stroke): (Value, f32, Value, Value)| {
+                lua.create_any_userdata(Shape::Circle(CircleShape {
+                    center: Pos2::from_lua(center)?,
+                    radius,
+                    fill: Color32::from_lua(fill)?,
+                    stroke: Stroke::from_lua(stroke)?,
+                }))
             },
-        );
-        reg.add_method(
-            "cursor",
-            |lua, this, ()| {
-                Rect::to_lua(this.cursor(), lua)
+        )?,
+    )?;
+    shape.set(
+        "rect",
+        lua.create_function(
+            |lua, (rect, rounding, fill, stroke): (Value, Value, Value, Value)| {
+                lua.create_any_userdata(Shape::Rect(RectShape::new(
+                    Rect::from_lua(rect)?,
+                    Rounding::from_lua(rounding)?,
+                    Color32::from_lua(fill)?,
+                    Stroke::from_lua(stroke)?,
+                )))
             },
-        );
-        reg.add_method_mut(
-            "data",
-            |lua, this, add_contents:  Function| {
-                let ir = this.data(  |reader| {
-                    lua.scope(|scope| {
-                        let reader = scope.create_any_userdata_ref(reader)?;
-                        let result: Result<MultiValue> = add_contents.call(reader);
-                        result
-                    })
-                });
-                ir
+        )?,
+    )?;
+    shape.set(
+        "path",
+        lua.create_function(
+            |lua, (points, closed, fill, stroke): (Table, bool, Value, Value)| {
+                lua.create_any_userdata(Shape::Path(PathShape {
+                    points: points_from_lua(points)?,
+                    closed,
+                    fill: Color32::from_lua(fill)?,
+                    stroke: Stroke::from_lua(stroke)?,
+                }))
             },
-        );
-        reg.add_method_mut(
-            "data_mut",
-            |lua, this, add_contents:  Function| {
-                let ir = this.data_mut(  |reader| {
-                    lua.scope(|scope| {
-                        let reader = scope.create_any_userdata_ref_mut(reader)?;
-                        let result: Result<MultiValue> = add_contents.call(reader);
-                        result
-                    })
-                });
-                ir
+        )?,
+    )?;
+    shape.set(
+        "convex_polygon",
+        lua.create_function(|lua, (points, fill, stroke): (Table, Value, Value)| {
+            lua.create_any_userdata(Shape::convex_polygon(
+                points_from_lua(points)?,
+                Color32::from_lua(fill)?,
+                Stroke::from_lua(stroke)?,
+            ))
+        })?,
+    )?;
+    shape.set(
+        "cubic_bezier",
+        lua.create_function(
+            |lua, (points, closed, fill, stroke): (Table, bool, Value, Value)| {
+                let points: [Pos2; 4] = points_from_lua(points)?.try_into().map_err(|_| {
+                    mlua::Error::RuntimeError("cubic bezier needs exactly 4 points".to_owned())
+                })?;
+                lua.create_any_userdata(Shape::CubicBezier(CubicBezierShape::from_points_stroke(
+                    points,
+                    closed,
+                    Color32::from_lua(fill)?,
+                    Stroke::from_lua(stroke)?,
+                )))
             },
-        );
-
-        // reg.add_method(
-        //     "debug_paint_cursor",
-        //     |_, this, ()| {
-        //         Ok(this.debug_paint_cursor())
-        //     },
-        // );
-        reg.add_method_mut("drag_angle", |lua, this, value: Table| {
-            let mut b: f32 = value.get("value")?;
-            let result = lua.create_any_userdata(this.drag_angle(&mut b));
-            value.set("value", b)?;
-            result
-        });
-
-        reg.add_method_mut("drag_angle_tau", |lua, this, value: Table| {
-            let mut b: f32 = value.get("value")?;
-            let result = lua.create_any_userdata(this.drag_angle_tau(&mut b));
-            value.set("value", b)?;
-            result
-        });
-        reg.add_method_mut(
-            "end_row",
-            |_, this, ()| {
-                Ok(this.end_row())
+        )?,
+    )?;
+    shape.set(
+        "quadratic_bezier",
+        lua.create_function(
+            |lua, (points, closed, fill, stroke): (Table, bool, Value, Value)| {
+                let points: [Pos2; 3] = points_from_lua(points)?.try_into().map_err(|_| {
+                    mlua::Error::RuntimeError("quadratic bezier needs exactly 3 points".to_owned())
+                })?;
+                lua.create_any_userdata(Shape::QuadraticBezier(
+                    QuadraticBezierShape::from_points_stroke(
+                        points,
+                        closed,
+                        Color32::from_lua(fill)?,
+                        Stroke::from_lua(stroke)?,
+                    ),
+                ))
             },
-        );
-
-        reg.add_method_mut(
-            "expand_to_include_rect",
-            |_, this, rect: Value| {
-                Ok(this.expand_to_include_rect(Rect::from_lua(rect)?))
+        )?,
+    )?;
+    shape.set(
+        "text",
+        lua.create_function(
+            |lua,
+             (ctx, pos, anchor, text, font_id, color): (
+                UserDataRef<Context>,
+                Value,
+                Value,
+                String,
+                Value,
+                Value,
+            )| {
+                let pos = Pos2::from_lua(pos)?;
+                let anchor = Align2::from_lua(anchor)?;
+                let font_id = ctx.fonts(|fonts| font_id_from_lua(fonts, font_id))?;
+                let color = Color32::from_lua(color)?;
+                let shape =
+                    ctx.fonts(|fonts| Shape::text(fonts, pos, anchor, text, font_id, color));
+                lua.create_any_userdata(shape)
             },
-        );
+        )?,
+    )?;
+    shape.set(
+        "mesh",
+        lua.create_function(|lua, (vertices, indices): (Table, Vec<u32>)| {
+            let vertices = vertices
+                .sequence_values::<Table>()
+                .map(|v| {
+                    let v = v?;
+                    let uv: Value = v.get("uv")?;
+                    Ok(Vertex {
+                        pos: Pos2::from_lua(v.get("pos")?)?,
+                        uv: if uv.is_nil() {
+                            WHITE_UV
+                        } else {
+                            Pos2::from_lua(uv)?
+                        },
+                        color: Color32::from_lua(v.get("color")?)?,
+                    })
+                })
+                .collect::<Result<Vec<Vertex>>>()?;
+            lua.create_any_userdata(Shape::mesh(Mesh {
+                indices,
+                vertices,
+                ..Default::default()
+            }))
+        })?,
+    )?;
+    shape.set(
+        "vec",
+        lua.create_function(|lua, shapes: Table| {
+            lua.create_any_userdata(Shape::Vec(shapes_from_lua(shapes)?))
+        })?,
+    )?;
+    egui_table.set("shape", shape)?;
+    Ok(())
+}
+fn add_spacing(lua: &Lua, egui_table: &Table) -> mlua::Result<()> {
+    lua.register_userdata_type(|reg: &mut UserDataRegistry<Spacing>| {
+        reg.add_field_method_get("item_spacing", |lua, this| {
+            Vec2::to_lua(this.item_spacing, lua)
+        });
+        reg.add_field_method_get("window_margin", |lua, this| {
+            Margin::to_lua(this.window_margin, lua)
+        });
+        reg.add_field_method_get("button_padding", |lua, this| {
+            Vec2::to_lua(this.button_padding, lua)
+        });
+        reg.add_field_method_get("menu_margin", |lua, this| {
+            Margin::to_lua(this.menu_margin, lua)
+        });
+        reg.add_field_method_get("indent", |_, this| Ok(this.indent));
 
-        reg.add_method_mut(
-            "expand_to_include_x",
-            |_, this, x: f32| {
-                Ok(this.expand_to_include_x(x))
-            },
-        );
-        reg.add_method_mut(
-            "expand_to_include_y",
-            |_, this, x: f32| {
-                Ok(this.expand_to_include_y(x))
+        reg.add_field_method_get("interact_size", |lua, this| {
+            Vec2::to_lua(this.interact_size, lua)
+        });
+        reg.add_field_method_get("slider_width", |_, this| Ok(this.slider_width));
+        reg.add_field_method_get("combo_width", |_, this| Ok(this.combo_width));
+        reg.add_field_method_get("text_edit_width", |_, this| Ok(this.text_edit_width));
+        reg.add_field_method_get("icon_width", |_, this| Ok(this.icon_width));
+        reg.add_field_method_get("icon_width_inner", |_, this| Ok(this.icon_width_inner));
+        reg.add_field_method_get("icon_spacing", |_, this| Ok(this.icon_spacing));
+        reg.add_field_method_get("tooltip_width", |_, this| Ok(this.tooltip_width));
+        reg.add_field_method_get("menu_width", |_, this| Ok(this.menu_width));
+        reg.add_field_method_get("indent_ends_with_horizontal_line", |_, this| {
+            Ok(this.indent_ends_with_horizontal_line)
+        });
+        reg.add_field_method_get("combo_height", |_, this| Ok(this.combo_height));
+        reg.add_field_method_get("scroll", |lua, this| lua.create_any_userdata(this.scroll));
+
+        reg.add_field_method_set("item_spacing", |_, this, value: Value| {
+            this.item_spacing = Vec2::from_lua(value)?;
+            Ok(())
+        });
+        reg.add_field_method_set("window_margin", |_, this, value: Value| {
+            this.window_margin = Margin::from_lua(value)?;
+            Ok(())
+        });
+        reg.add_field_method_set("button_padding", |_, this, value: Value| {
+            this.button_padding = Vec2::from_lua(value)?;
+            Ok(())
+        });
+        reg.add_field_method_set("menu_margin", |_, this, value: Value| {
+            this.menu_margin = Margin::from_lua(value)?;
+            Ok(())
+        });
+        reg.add_field_method_set("indent", |_, this, value: f32| {
+            this.indent = value;
+            Ok(())
+        });
+        reg.add_field_method_set("interact_size", |_, this, value: Value| {
+            this.interact_size = Vec2::from_lua(value)?;
+            Ok(())
+        });
+        reg.add_field_method_set("slider_width", |_, this, value: f32| {
+            this.slider_width = value;
+            Ok(())
+        });
+        reg.add_field_method_set("combo_width", |_, this, value: f32| {
+            this.combo_width = value;
+            Ok(())
+        });
+        reg.add_field_method_set("text_edit_width", |_, this, value: f32| {
+            this.text_edit_width = value;
+            Ok(())
+        });
+        reg.add_field_method_set("icon_width", |_, this, value: f32| {
+            this.icon_width = value;
+            Ok(())
+        });
+        reg.add_field_method_set("icon_width_inner", |_, this, value: f32| {
+            this.icon_width_inner = value;
+            Ok(())
+        });
+        reg.add_field_method_set("icon_spacing", |_, this, value: f32| {
+            this.icon_spacing = value;
+            Ok(())
+        });
+        reg.add_field_method_set("tooltip_width", |_, this, value: f32| {
+            this.tooltip_width = value;
+            Ok(())
+        });
+        reg.add_field_method_set("menu_width", |_, this, value: f32| {
+            this.menu_width = value;
+            Ok(())
+        });
+        reg.add_field_method_set(
+            "indent_ends_with_horizontal_line",
+            |_, this, value: bool| {
+                this.indent_ends_with_horizontal_line = value;
+                Ok(())
             },
         );
+        reg.add_field_method_set("combo_height", |_, this, value: f32| {
+            this.combo_height = value;
+            Ok(())
+        });
+        reg.add_field_method_set("scroll", |_, this, value: UserDataRef<ScrollStyle>| {
+            this.scroll = *value;
+            Ok(())
+        });
+    })?;
+    lua.register_userdata_type(|reg: &mut UserDataRegistry<ScrollStyle>| {
+        reg.add_field_method_get("floating", |_, this| Ok(this.floating));
+        reg.add_field_method_get("bar_width", |_, this| Ok(this.bar_width));
+        reg.add_field_method_get("handle_min_length", |_, this| Ok(this.handle_min_length));
+        reg.add_field_method_get("bar_inner_margin", |_, this| Ok(this.bar_inner_margin));
+        reg.add_field_method_get("bar_outer_margin", |_, this| Ok(this.bar_outer_margin));
+        reg.add_field_method_get("floating_width", |_, this| Ok(this.floating_width));
+        reg.add_field_method_get("floating_allocated_width", |_, this| {
+            Ok(this.floating_allocated_width)
+        });
+        reg.add_field_method_get("foreground_color", |_, this| Ok(this.foreground_color));
+        reg.add_field_method_get("dormant_background_opacity", |_, this| {
+            Ok(this.dormant_background_opacity)
+        });
+        reg.add_field_method_get("active_background_opacity", |_, this| {
+            Ok(this.active_background_opacity)
+        });
+        reg.add_field_method_get("interact_background_opacity", |_, this| {
+            Ok(this.interact_background_opacity)
+        });
+        reg.add_field_method_get("dormant_handle_opacity", |_, this| {
+            Ok(this.dormant_handle_opacity)
+        });
+        reg.add_field_method_get("active_handle_opacity", |_, this| {
+            Ok(this.active_handle_opacity)
+        });
+        reg.add_field_method_get("interact_handle_opacity", |_, this| {
+            Ok(this.interact_handle_opacity)
+        });
 
-        reg.add_method_mut(
-            "fonts",
-            |lua, this, add_contents:  Function| {
-                let ir = this.fonts(  |reader| {
-                    lua.scope(|scope| {
-                        let reader = scope.create_any_userdata_ref(reader)?;
-                        let result: Result<MultiValue> = add_contents.call(reader);
-                        result
-                    })
+        reg.add_field_method_set("floating", |_, this, value: bool| {
+            this.floating = value;
+            Ok(())
+        });
+        reg.add_field_method_set("bar_width", |_, this, value: f32| {
+            this.bar_width = value;
+            Ok(())
+        });
+        reg.add_field_method_set("handle_min_length", |_, this, value: f32| {
+            this.handle_min_length = value;
+            Ok(())
+        });
+        reg.add_field_method_set("bar_inner_margin", |_, this, value: f32| {
+            this.bar_inner_margin = value;
+            Ok(())
+        });
+        reg.add_field_method_set("bar_outer_margin", |_, this, value: f32| {
+            this.bar_outer_margin = value;
+            Ok(())
+        });
+        reg.add_field_method_set("floating_width", |_, this, value: f32| {
+            this.floating_width = value;
+            Ok(())
+        });
+        reg.add_field_method_set("floating_allocated_width", |_, this, value: f32| {
+            this.floating_allocated_width = value;
+            Ok(())
+        });
+        reg.add_field_method_set("foreground_color", |_, this, value: bool| {
+            this.foreground_color = value;
+            Ok(())
+        });
+        reg.add_field_method_set("dormant_background_opacity", |_, this, value: f32| {
+            this.dormant_background_opacity = value;
+            Ok(())
+        });
+        reg.add_field_method_set("active_background_opacity", |_, this, value: f32| {
+            this.active_background_opacity = value;
+            Ok(())
+        });
+        reg.add_field_method_set("interact_background_opacity", |_, this, value: f32| {
+            this.interact_background_opacity = value;
+            Ok(())
+        });
+        reg.add_field_method_set("dormant_handle_opacity", |_, this, value: f32| {
+            this.dormant_handle_opacity = value;
+            Ok(())
+        });
+        reg.add_field_method_set("active_handle_opacity", |_, this, value: f32| {
+            this.active_handle_opacity = value;
+            Ok(())
+        });
+        reg.add_field_method_set("interact_handle_opacity", |_, this, value: f32| {
+            this.interact_handle_opacity = value;
+            Ok(())
+        });
+    })?;
+    let scroll_style = lua.create_table()?;
+    scroll_style.set(
+        "solid",
+        lua.create_function(|lua, _: ()| lua.create_any_userdata(ScrollStyle::solid()))?,
+    )?;
+    scroll_style.set(
+        "thin",
+        lua.create_function(|lua, _: ()| lua.create_any_userdata(ScrollStyle::thin()))?,
+    )?;
+    scroll_style.set(
+        "floating",
+        lua.create_function(|lua, _: ()| lua.create_any_userdata(ScrollStyle::floating()))?,
+    )?;
+    egui_table.set("scroll_style", scroll_style)?;
+    Ok(())
+}
+fn add_response(lua: &Lua) -> mlua::Result<()> {
+    lua.register_userdata_type(|reg: &mut UserDataRegistry<egui::Response>| {
+        reg.add_field_method_get("rect", |lua, this| Rect::to_lua(this.rect, lua));
+        reg.add_field_method_get("id", |lua, this| Id::to_lua(this.id, lua));
+        reg.add_field_method_get("sense", |lua, this| Sense::to_lua(this.sense, lua));
+
+        reg.add_method("changed", |_, this, ()| Ok(this.changed()));
+        reg.add_method("clicked", |_, this, ()| Ok(this.clicked()));
+        reg.add_method("clicked_by", |_, this, value: Value| {
+            Ok(this.clicked_by(PointerButton::from_lua(value)?))
+        });
+        reg.add_method("clicked_elsewhere", |_, this, ()| {
+            Ok(this.clicked_elsewhere())
+        });
+        reg.add_method("context_menu", |lua, this, add_contents: Function| {
+            let mut result = Ok(());
+            let ir = this.context_menu(|ui| {
+                result = lua.scope(|scope| {
+                    let ui = scope.create_any_userdata_ref_mut(ui)?;
+                    add_contents.call(ui)
                 });
-                ir
-            },
-        );
+            });
+            result?;
+            ir.map(|ir| lua.create_any_userdata(ir.response))
+                .transpose()
+        });
+        reg.add_method("double_clicked", |_, this, ()| Ok(this.double_clicked()));
+        reg.add_method("double_clicked_by", |_, this, value: Value| {
+            Ok(this.double_clicked_by(PointerButton::from_lua(value)?))
+        });
+        reg.add_method("drag_delta", |lua, this, ()| {
+            Ok(Vec2::to_lua(this.drag_delta(), lua))
+        });
+        reg.add_method("drag_released", |_, this, ()| Ok(this.drag_released()));
+        reg.add_method("drag_released_by", |_, this, value: Value| {
+            Ok(this.drag_released_by(PointerButton::from_lua(value)?))
+        });
+        reg.add_method("drag_started", |_, this, ()| Ok(this.drag_started()));
+        // newer egui versions renamed `drag_released` to `drag_stopped`
+        reg.add_method("drag_stopped", |_, this, ()| Ok(this.drag_released()));
+        reg.add_method("drag_started_by", |_, this, value: Value| {
+            Ok(this.drag_started_by(PointerButton::from_lua(value)?))
+        });
+        reg.add_method("dragged", |_, this, ()| Ok(this.dragged()));
+        reg.add_method("dragged_by", |_, this, value: Value| {
+            Ok(this.dragged_by(PointerButton::from_lua(value)?))
+        });
+        reg.add_method("enabled", |_, this, ()| Ok(this.enabled()));
+        reg.add_method("gained_focus", |_, this, ()| Ok(this.gained_focus()));
+        reg.add_method("has_focus", |_, this, ()| Ok(this.has_focus()));
+        reg.add_method("highlight", |lua, this, ()| {
+            lua.create_any_userdata(this.clone().highlight())
+        });
+        reg.add_method("hover_pos", |lua, this, ()| {
+            Ok(this.hover_pos().and_then(|p| Pos2::to_lua(p, lua).ok()))
+        });
+        reg.add_method("hovered", |_, this, ()| Ok(this.hovered()));
+        reg.add_method("interact_pointer_pos", |lua, this, ()| {
+            Ok(this
+                .interact_pointer_pos()
+                .and_then(|p| Pos2::to_lua(p, lua).ok()))
+        });
+        reg.add_method("lost_focus", |_, this, ()| Ok(this.lost_focus()));
+        reg.add_method_mut("mark_changed", |_, this, ()| {
+            this.mark_changed();
+            Ok(())
+        });
+        reg.add_method("middle_clicked", |_, this, ()| Ok(this.middle_clicked()));
+        reg.add_method("on_disabled_hover_text", |lua, this, text: Value| {
+            lua.create_any_userdata(
+                this.clone()
+                    .on_disabled_hover_text(widget_text_from_lua(lua, text)?),
+            )
+        });
+        reg.add_method("on_hover_cursor", |lua, this, cursor: Value| {
+            lua.create_any_userdata(this.clone().on_hover_cursor(CursorIcon::from_lua(cursor)?))
+        });
+        reg.add_method("on_hover_text", |lua, this, text: Value| {
+            lua.create_any_userdata(this.clone().on_hover_text(widget_text_from_lua(lua, text)?))
+        });
+        reg.add_method("on_hover_ui", |lua, this, add_contents: Function| {
+            let mut result = Ok(());
+            let response = this.clone().on_hover_ui(|ui| {
+                result = lua.scope(|scope| {
+                    let ui = scope.create_any_userdata_ref_mut(ui)?;
+                    add_contents.call(ui)
+                });
+            });
+            result?;
+            lua.create_any_userdata(response)
+        });
+        reg.add_method("request_focus", |_, this, ()| {
+            this.request_focus();
+            Ok(())
+        });
+        reg.add_method("scroll_to_me", |_, this, align: Value| {
+            this.scroll_to_me(if align.is_nil() {
+                None
+            } else {
+                Some(Align::from_lua(align)?)
+            });
+            Ok(())
+        });
+        reg.add_method("secondary_clicked", |_, this, ()| {
+            Ok(this.secondary_clicked())
+        });
+        reg.add_method("surrender_focus", |_, this, ()| {
+            this.surrender_focus();
+            Ok(())
+        });
+        reg.add_method("triple_clicked", |_, this, ()| Ok(this.triple_clicked()));
+        reg.add_method("union", |lua, this, other: UserDataRef<egui::Response>| {
+            lua.create_any_userdata(this.union(other.clone()))
+        });
+    })
+}
 
+fn add_ui(lua: &Lua, _egui_table: &Table) -> mlua::Result<()> {
+    lua.register_userdata_type(|reg: &mut UserDataRegistry<Ui>| {
+        reg.add_method_mut("add", |lua, this, widget: AnyUserData| {
+            lua.create_any_userdata(add_lua_widget(lua, widget, |w| this.add(w))?)
+        });
+        reg.add_method_mut("add_enabled", |lua, this, (enabled, widget): (bool, AnyUserData)| {
+            lua.create_any_userdata(add_lua_widget(lua, widget, |w| this.add_enabled(enabled, w))?)
+        });
         reg.add_method_mut(
-            "group",
-            |lua, this, add_contents: Function| {
-                let ir = this.group(|ui| {
+            "add_enabled_ui",
+            |lua, this, (enabled, add_contents): (bool, Function)| {
+                let ir = this.add_enabled_ui(enabled, |ui| {
                     lua.scope(|scope| {
                         let ui = scope.create_any_userdata_ref_mut(ui)?;
                         let result: Result<MultiValue> = add_contents.call(ui);
@@ -668,13 +1467,19 @@ fn add_ui(lua: &Lua, _egui_table: &Table) -> mlua::Result<()> {
                 Ok(i)
             },
         );
-        reg.add_method_mut("heading", |lua, this, value: Value| {
-            lua.create_any_userdata(this.heading(RichText::from_lua(value)?))
+
+        reg.add_method_mut("add_sized", |lua, this, (size, widget): (Value, AnyUserData)| {
+            let size = Vec2::from_lua(size)?;
+            lua.create_any_userdata(add_lua_widget(lua, widget, |w| this.add_sized(size, w))?)
+        });
+        reg.add_method_mut("add_space", |_, this, amount: f32| {
+            this.add_space(amount);
+            Ok(())
         });
         reg.add_method_mut(
-            "horizontal",
-            |lua, this, add_contents: Function| {
-                let ir = this.horizontal(|ui| {
+            "add_visible_ui",
+            |lua, this, (visible, add_contents): (bool, Function)| {
+                let ir = this.add_visible_ui(visible, |ui| {
                     lua.scope(|scope| {
                         let ui = scope.create_any_userdata_ref_mut(ui)?;
                         let result: Result<MultiValue> = add_contents.call(ui);
@@ -687,30 +1492,57 @@ fn add_ui(lua: &Lua, _egui_table: &Table) -> mlua::Result<()> {
                 Ok(i)
             },
         );
-
         reg.add_method_mut(
-            "horizontal_centered",
-            |lua, this, add_contents: Function| {
-                let ir = this.horizontal_centered(|ui| {
-                    lua.scope(|scope| {
-                        let ui = scope.create_any_userdata_ref_mut(ui)?;
-                        let result: Result<MultiValue> = add_contents.call(ui);
-                        result
-                    })
-                });
-                let r = lua.create_any_userdata(ir.response)?;
-                let mut i = ir.inner?;
-                i.push_front(Value::UserData(r));
-                Ok(i)
+            "allocate_at_least",
+            |lua, this, (desired_size, sense): (Value, Value)| {
+                let (rect, resp) = this.allocate_at_least(
+                    LuaHelperTrait::from_lua(desired_size)?,
+                    LuaHelperTrait::from_lua(sense)?,
+                );
+
+                Ok((Rect::to_lua(rect, lua)?, lua.create_any_userdata(resp)?))
             },
         );
+        reg.add_method_mut(
+            "allocate_exact_size",
+            |lua, this, (desired_size, sense): (Value, Value)| {
+                let (rect, resp) = this.allocate_exact_size(
+                    LuaHelperTrait::from_lua(desired_size)?,
+                    LuaHelperTrait::from_lua(sense)?,
+                );
 
+                Ok((Rect::to_lua(rect, lua)?, lua.create_any_userdata(resp)?))
+            },
+        );
         reg.add_method_mut(
-            "horizontal_top",
-            |lua, this, add_contents: Function| {
-                let ir = this.horizontal_top(|ui| {
-                    lua.scope(|scope| {
-                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+            "allocate_rect",
+            |lua, this, (rect, sense): (Value, Value)| {
+                lua.create_any_userdata(this.allocate_rect(
+                    LuaHelperTrait::from_lua(rect)?,
+                    LuaHelperTrait::from_lua(sense)?,
+                ))
+            },
+        );
+        reg.add_method_mut(
+            "allocate_response",
+            |lua, this, (desired_size, sense): (Value, Value)| {
+                lua.create_any_userdata(this.allocate_response(
+                    LuaHelperTrait::from_lua(desired_size)?,
+                    LuaHelperTrait::from_lua(sense)?,
+                ))
+            },
+        );
+
+        reg.add_method_mut("allocate_space", |lua, this, desired_size: Value| {
+            let (id, rect) = this.allocate_space(LuaHelperTrait::from_lua(desired_size)?);
+            Ok((lua.create_any_userdata(id)?, Rect::to_lua(rect, lua)?))
+        });
+        reg.add_method_mut(
+            "allocate_ui",
+            |lua, this, (desired_size, add_contents): (Value, Function)| {
+                let ir = this.allocate_ui(Vec2::from_lua(desired_size)?, |ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
                         let result: Result<MultiValue> = add_contents.call(ui);
                         result
                     })
@@ -721,11 +1553,10 @@ fn add_ui(lua: &Lua, _egui_table: &Table) -> mlua::Result<()> {
                 Ok(i)
             },
         );
-
         reg.add_method_mut(
-            "horizontal_wrapped",
-            |lua, this, add_contents: Function| {
-                let ir = this.horizontal_wrapped(|ui| {
+            "allocate_ui_at_rect",
+            |lua, this, (max_rect, add_contents): (Value, Function)| {
+                let ir = this.allocate_ui_at_rect(Rect::from_lua(max_rect)?, |ui| {
                     lua.scope(|scope| {
                         let ui = scope.create_any_userdata_ref_mut(ui)?;
                         let result: Result<MultiValue> = add_contents.call(ui);
@@ -738,24 +1569,44 @@ fn add_ui(lua: &Lua, _egui_table: &Table) -> mlua::Result<()> {
                 Ok(i)
             },
         );
-
-        reg.add_method_mut("hyperlink", |lua, this, value: String| {
-            lua.create_any_userdata(this.hyperlink(value))
-        });
-
-        reg.add_method_mut("hyperlink_to", |lua, this, (label, url): (Value,  String)| {
-            lua.create_any_userdata(this.hyperlink_to(WidgetText::from_lua(label)?, url ))
+        reg.add_method_mut(
+            "allocate_ui_with_layout",
+            |lua, this, (desired_size, layout, add_contents): (Value, UserDataRef<Layout>, Function)| {
+                let ir = this.allocate_ui_with_layout(Vec2::from_lua(desired_size)?, *layout,|ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_contents.call(ui);
+                        result
+                    })
+                });
+                let r = lua.create_any_userdata(ir.response)?;
+                let mut i = ir.inner?;
+                i.push_front(Value::UserData(r));
+                Ok(i)
+            },
+        );
+        reg.add_method("auto_id_with", |lua, this, value: Value| {
+            lua.create_any_userdata(match value {
+              Value::Boolean(b) => {
+                this.auto_id_with(b)
+                },
+                Value::Integer(b) => this.auto_id_with(b),
+                Value::String(b) => this.auto_id_with(b),
+                _ => return Err(mlua::Error::external("value type cannot be hashed to get new id"))
+            })
         });
-
-        reg.add_method("id", |lua, this, ()| lua.create_any_userdata(this.id()));
-
-        reg.add_method_mut("image", |lua, this, (texture, size): (UserDataRef<TextureHandle>,  Value)| {
-            lua.create_any_userdata(this.image(SizedTexture::new(texture.id(), Vec2::from_lua(size)?) ))
+        reg.add_method("available_height", |_, this, ()|Ok(this.available_height()));
+        reg.add_method("available_rect_before_wrap", |lua, this, ()| Rect::to_lua(this.available_rect_before_wrap(), lua));
+        reg.add_method("avaialble_size", |lua, this, ()| Vec2::to_lua(this.available_size(), lua));
+        reg.add_method("avaialble_size_before_wrap", |lua, this, ()| Vec2::to_lua(this.available_size_before_wrap(), lua));
+        reg.add_method("available_width", |_, this, ()|Ok(this.available_width()));
+        reg.add_method_mut("button", |lua, this, value: Value| {
+            lua.create_any_userdata(this.button(widget_text_from_lua(lua, value)?))
         });
         reg.add_method_mut(
-            "indent",
-            |lua, this, (hashable, add_contents): (Value, Function)| {
-                let ir = this.indent( LuaHashable::from_lua(hashable)?, |ui| {
+            "centered_and_justified",
+            |lua, this, add_contents: Function| {
+                let ir = this.centered_and_justified(|ui| {
                     lua.scope(|scope| {
                         let ui = scope.create_any_userdata_ref_mut(ui)?;
                         let result: Result<MultiValue> = add_contents.call(ui);
@@ -768,73 +1619,79 @@ fn add_ui(lua: &Lua, _egui_table: &Table) -> mlua::Result<()> {
                 Ok(i)
             },
         );
-
+        reg.add_method_mut("checkbox", |lua, this, value: Table| {
+            let mut b: bool = value.get("checked")?;
+            let result = lua.create_any_userdata(this.checkbox(&mut b, widget_text_from_lua(lua, value.get("text")?)?));
+            value.set("checked", b)?;
+            result
+        });
+        reg.add_method_mut("child_ui", |lua, this, (max_rect, layout): (Value, UserDataRef<Layout>)| {
+            let ui =  this.child_ui(LuaHelperTrait::from_lua(max_rect)?, *layout);
+            lua.create_any_userdata(ui)
+        });
+        // requires impl Hash for Value smh
+        // reg.add_method_mut("child_ui_with_id_source", |lua, this, (max_rect, layout): (Value, Value)| {
+        //     let ui =  this.child_ui(LuaHelperTrait::from_lua(max_rect)?, LuaHelperTrait::from_lua(layout)?);
+        //     lua.create_any_userdata(ui)
+        // });
+        reg.add_method("clip_rect", |lua, this, ()| {
+            Rect::to_lua(this.clip_rect(), lua)
+        });
+        reg.add_method_mut("close_menu", |_, this, ()| {
+            this.close_menu();
+            Ok(())
+        });
+        reg.add_method_mut("code", |lua, this, value: Value| {
+            lua.create_any_userdata(this.code(rich_text_from_lua(lua, value)?))
+        });
+        reg.add_method_mut("code_editor", |lua, this, value: Table| {
+            let mut b: String = value.get("text")?;
+            let result = lua.create_any_userdata(this.code_editor(&mut b));
+            value.set("text", b)?;
+            result
+        });
         reg.add_method_mut(
-            "input",
-            |lua, this, add_contents:  Function| {
-                let ir = this.input(  |reader| {
+            "collapsing",
+            |lua, this, (heading, add_contents): (Value, Function)| {
+                let ir = this.collapsing(widget_text_from_lua(lua, heading)?, |ui| {
                     lua.scope(|scope| {
-                        let reader = scope.create_any_userdata_ref(reader)?;
-                        let result: Result<MultiValue> = add_contents.call(reader);
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_contents.call(ui);
                         result
                     })
                 });
-                ir
+                collapsing_response_to_lua(lua, ir)
             },
         );
         reg.add_method_mut(
-            "input_mut",
-            |lua, this, add_contents:  Function| {
-                let ir = this.input_mut(  |reader| {
+            "columns",
+            |lua, this, (num, add_contents): (usize, Function)| {
+                let ir = this.columns( num, |cols| {
                     lua.scope(|scope| {
-                        let reader = scope.create_any_userdata_ref_mut(reader)?;
-                        let result: Result<MultiValue> = add_contents.call(reader);
+                        let cols: Vec<AnyUserData> = cols.into_iter().map(|ui| scope.create_any_userdata_ref_mut(ui)).collect::<Result<Vec<AnyUserData>>>()?;
+                        let result: Result<MultiValue> = add_contents.call(cols);
                         result
                     })
                 });
                 ir
             },
         );
-
-        reg.add_method("interact", |lua, this, (rect, id, sense): (Value, UserDataRef<Id>, Value)| {
-            lua.create_any_userdata( this.interact(Rect::from_lua(rect)?, *id, Sense::from_lua(sense)?))
-        });
-
-        reg.add_method("interact_with_hovered", |lua, this, (rect, hovered, id, sense): (Value, bool, UserDataRef<Id>, Value)| {
-            lua.create_any_userdata( this.interact_with_hovered(Rect::from_lua(rect)?, hovered, *id, Sense::from_lua(sense)?))
-        });
-        reg.add_method("is_enabled", |_, this, ()| Ok(this.is_enabled()));        
-        reg.add_method_mut("is_rect_visible", |_, this, clip_rect: Value| {
-            Ok(this.is_rect_visible(Rect::from_lua(clip_rect)?))
-        });
-        reg.add_method("is_visible", |_, this, ()| Ok(this.is_visible()));
-        reg.add_method_mut("label", |lua, this, value: Value| {
-            lua.create_any_userdata(this.label(WidgetText::from_lua(value)?))
-        });
-        reg.add_method("layer_id", |lua, this, () | {
-            lua.create_any_userdata(this.layer_id())
-        });
-
-        reg.add_method("layout", |lua, this, () | {
-            lua.create_any_userdata(this.layout().clone())
-        });
-
-        reg.add_method_mut("link", |lua, this, value: Value| {
-            lua.create_any_userdata(this.link(WidgetText::from_lua(value)?))
-        });
-
-        reg.add_method_mut("make_persistent_id", |lua, this, value: Value| {
-            lua.create_any_userdata(this.make_persistent_id(LuaHashable::from_lua(value)?))
-        });
-
-        reg.add_method("max_rect", |lua, this, ()| {
-            Rect::to_lua(this.max_rect(), lua)
-        });
-
         reg.add_method(
-            "memory",
+            "ctx",
+            |lua, this, ()| {
+                lua.create_any_userdata(this.ctx().clone())
+            },
+        );
+        reg.add_method(
+            "cursor",
+            |lua, this, ()| {
+                Rect::to_lua(this.cursor(), lua)
+            },
+        );
+        reg.add_method_mut(
+            "data",
             |lua, this, add_contents:  Function| {
-                let ir = this.memory(  |reader| {
+                let ir = this.data(  |reader| {
                     lua.scope(|scope| {
                         let reader = scope.create_any_userdata_ref(reader)?;
                         let result: Result<MultiValue> = add_contents.call(reader);
@@ -845,9 +1702,9 @@ fn add_ui(lua: &Lua, _egui_table: &Table) -> mlua::Result<()> {
             },
         );
         reg.add_method_mut(
-            "memory_mut",
+            "data_mut",
             |lua, this, add_contents:  Function| {
-                let ir = this.memory_mut(  |reader| {
+                let ir = this.data_mut(  |reader| {
                     lua.scope(|scope| {
                         let reader = scope.create_any_userdata_ref_mut(reader)?;
                         let result: Result<MultiValue> = add_contents.call(reader);
@@ -857,42 +1714,57 @@ fn add_ui(lua: &Lua, _egui_table: &Table) -> mlua::Result<()> {
                 ir
             },
         );
+
+        // reg.add_method(
+        //     "debug_paint_cursor",
+        //     |_, this, ()| {
+        //         Ok(this.debug_paint_cursor())
+        //     },
+        // );
+        reg.add_method_mut("drag_angle", |lua, this, value: Table| {
+            let mut b: f32 = value.get("value")?;
+            let result = lua.create_any_userdata(this.drag_angle(&mut b));
+            value.set("value", b)?;
+            result
+        });
+
+        reg.add_method_mut("drag_angle_tau", |lua, this, value: Table| {
+            let mut b: f32 = value.get("value")?;
+            let result = lua.create_any_userdata(this.drag_angle_tau(&mut b));
+            value.set("value", b)?;
+            result
+        });
+        reg.add_method_mut(
+            "end_row",
+            |_, this, ()| {
+                Ok(this.end_row())
+            },
+        );
+
         reg.add_method_mut(
-            "menu_button",
-            |lua, this, (title, add_contents): (Value, Function)| {
-                let ir = this.menu_button(WidgetText::from_lua(title)?, |ui| {
-                    lua.scope(|scope| {
-                        let ui = scope.create_any_userdata_ref_mut(ui)?;
-                        let result: Result<MultiValue> = add_contents.call(ui);
-                        result
-                    })
-                });
+            "expand_to_include_rect",
+            |_, this, rect: Value| {
+                Ok(this.expand_to_include_rect(Rect::from_lua(rect)?))
+            },
+        );
 
-                let mut result = MultiValue::new();
-                let response = lua.create_any_userdata(ir.response)?;
-                result.push_front(Value::UserData(response));
-                if let Some(inner) = ir.inner {
-                    let inner = inner?;
-                    for v in inner {
-                        result.push_front(v);
-                    }
-                }
-                Ok(result)
+        reg.add_method_mut(
+            "expand_to_include_x",
+            |_, this, x: f32| {
+                Ok(this.expand_to_include_x(x))
             },
         );
-        reg.add_method_mut("selectable_label", |lua, ui, (selected, text): (bool, Value)| {
-            lua.create_any_userdata(ui.selectable_label(selected, WidgetText::from_lua(text)?))
-        });
         reg.add_method_mut(
-            "set_row_height",
-            |_, this, height: f32| {
-                Ok(this.set_row_height(height))
+            "expand_to_include_y",
+            |_, this, x: f32| {
+                Ok(this.expand_to_include_y(x))
             },
         );
+
         reg.add_method_mut(
-            "output",
+            "fonts",
             |lua, this, add_contents:  Function| {
-                let ir = this.output(  |reader| {
+                let ir = this.fonts(  |reader| {
                     lua.scope(|scope| {
                         let reader = scope.create_any_userdata_ref(reader)?;
                         let result: Result<MultiValue> = add_contents.call(reader);
@@ -902,143 +1774,464 @@ fn add_ui(lua: &Lua, _egui_table: &Table) -> mlua::Result<()> {
                 ir
             },
         );
+
         reg.add_method_mut(
-            "output_mut",
-            |lua, this, add_contents:  Function| {
-                let ir = this.output_mut(  |reader| {
+            "group",
+            |lua, this, add_contents: Function| {
+                let ir = this.group(|ui| {
                     lua.scope(|scope| {
-                        let reader = scope.create_any_userdata_ref_mut(reader)?;
-                        let result: Result<MultiValue> = add_contents.call(reader);
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_contents.call(ui);
                         result
                     })
                 });
-                ir
+                let r = lua.create_any_userdata(ir.response)?;
+                let mut i = ir.inner?;
+                i.push_front(Value::UserData(r));
+                Ok(i)
             },
         );
-        reg.add_method(
-            "next_widget_position",
-            |lua, this, ()| {
-                Pos2::to_lua(this.next_widget_position(), lua)
+        reg.add_method_mut("heading", |lua, this, value: Value| {
+            lua.create_any_userdata(this.heading(rich_text_from_lua(lua, value)?))
+        });
+        reg.add_method_mut(
+            "horizontal",
+            |lua, this, add_contents: Function| {
+                let ir = this.horizontal(|ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_contents.call(ui);
+                        result
+                    })
+                });
+                let r = lua.create_any_userdata(ir.response)?;
+                let mut i = ir.inner?;
+                i.push_front(Value::UserData(r));
+                Ok(i)
             },
         );
-        reg.add_method(
-            "painter",
-            |lua, this, ()| {
-                lua.create_any_userdata(this.painter().clone())
+
+        reg.add_method_mut(
+            "horizontal_centered",
+            |lua, this, add_contents: Function| {
+                let ir = this.horizontal_centered(|ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_contents.call(ui);
+                        result
+                    })
+                });
+                let r = lua.create_any_userdata(ir.response)?;
+                let mut i = ir.inner?;
+                i.push_front(Value::UserData(r));
+                Ok(i)
             },
         );
-        reg.add_method_mut("text_edit_multiline", |lua, this, value: Table| {
-            let mut b: String = value.get("text")?;
-            let result = lua.create_any_userdata(this.text_edit_multiline(&mut b));
-            value.set("text", b)?;
-            result
+
+        reg.add_method_mut(
+            "horizontal_top",
+            |lua, this, add_contents: Function| {
+                let ir = this.horizontal_top(|ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_contents.call(ui);
+                        result
+                    })
+                });
+                let r = lua.create_any_userdata(ir.response)?;
+                let mut i = ir.inner?;
+                i.push_front(Value::UserData(r));
+                Ok(i)
+            },
+        );
+
+        reg.add_method_mut(
+            "horizontal_wrapped",
+            |lua, this, add_contents: Function| {
+                let ir = this.horizontal_wrapped(|ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_contents.call(ui);
+                        result
+                    })
+                });
+                let r = lua.create_any_userdata(ir.response)?;
+                let mut i = ir.inner?;
+                i.push_front(Value::UserData(r));
+                Ok(i)
+            },
+        );
+
+        reg.add_method_mut("hyperlink", |lua, this, value: String| {
+            lua.create_any_userdata(this.hyperlink(value))
         });
-        reg.add_method_mut("text_edit_singleline", |lua, this, value: Table| {
-            let mut b: String = value.get("text")?;
-            let result = lua.create_any_userdata(this.text_edit_singleline(&mut b));
-            value.set("text", b)?;
-            result
+
+        reg.add_method_mut("hyperlink_to", |lua, this, (label, url): (Value,  String)| {
+            lua.create_any_userdata(this.hyperlink_to(widget_text_from_lua(lua, label)?, url ))
         });
-        reg.add_method_mut("set_enabled", |_, this, enabled: bool| {
-            this.set_enabled(enabled);
-            Ok(())
+
+        reg.add_method("id", |lua, this, ()| lua.create_any_userdata(this.id()));
+
+        reg.add_method_mut("image", |lua, this, (texture, size): (UserDataRef<TextureHandle>,  Value)| {
+            lua.create_any_userdata(this.image(SizedTexture::new(texture.id(), Vec2::from_lua(size)?) ))
         });
+        reg.add_method_mut(
+            "indent",
+            |lua, this, (hashable, add_contents): (Value, Function)| {
+                let ir = this.indent( LuaHashable::from_lua(hashable)?, |ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_contents.call(ui);
+                        result
+                    })
+                });
+                let r = lua.create_any_userdata(ir.response)?;
+                let mut i = ir.inner?;
+                i.push_front(Value::UserData(r));
+                Ok(i)
+            },
+        );
 
-        reg.add_method("wrap_text", |_, this, ()| Ok(this.wrap_text()));
+        reg.add_method_mut(
+            "input",
+            |lua, this, add_contents:  Function| {
+                let ir = this.input(  |reader| {
+                    lua.scope(|scope| {
+                        let reader = scope.create_any_userdata_ref(reader)?;
+                        let result: Result<MultiValue> = add_contents.call(reader);
+                        result
+                    })
+                });
+                ir
+            },
+        );
+        reg.add_method_mut(
+            "input_mut",
+            |lua, this, add_contents:  Function| {
+                let ir = this.input_mut(  |reader| {
+                    lua.scope(|scope| {
+                        let reader = scope.create_any_userdata_ref_mut(reader)?;
+                        let result: Result<MultiValue> = add_contents.call(reader);
+                        result
+                    })
+                });
+                ir
+            },
+        );
 
-        reg.add_method_mut("set_clip_rect", |_, this, clip_rect: Value| {
-            this.set_clip_rect(Rect::from_lua(clip_rect)?);
-            Ok(())
+        reg.add_method("interact", |lua, this, (rect, id, sense): (Value, UserDataRef<Id>, Value)| {
+            lua.create_any_userdata( this.interact(Rect::from_lua(rect)?, *id, Sense::from_lua(sense)?))
         });
-    })?;
 
-    Ok(())
-}
-impl LuaHelperTrait for Sense {
-    fn from_lua(value: Value) -> Result<Self> {
-        match value {
-            Value::Integer(i) => {
-                let i = i as u8;
-                let click = 0 != (i & 1);
-                let drag = 0 != (i & (1 << 1));
-                let focusable = 0 != (i & (1 << 2));
-                Ok(Self {
-                    click,
-                    drag,
-                    focusable,
-                })
-            }
-            _ => Err(mlua::Error::FromLuaConversionError {
-                from: "luavalue",
-                to: "pointerbutton",
-                message: None,
-            }),
-        }
-    }
+        reg.add_method("interact_with_hovered", |lua, this, (rect, hovered, id, sense): (Value, bool, UserDataRef<Id>, Value)| {
+            lua.create_any_userdata( this.interact_with_hovered(Rect::from_lua(rect)?, hovered, *id, Sense::from_lua(sense)?))
+        });
+        reg.add_method("is_enabled", |_, this, ()| Ok(this.is_enabled()));        
+        reg.add_method_mut("is_rect_visible", |_, this, clip_rect: Value| {
+            Ok(this.is_rect_visible(Rect::from_lua(clip_rect)?))
+        });
+        reg.add_method("is_visible", |_, this, ()| Ok(this.is_visible()));
+        reg.add_method_mut("label", |lua, this, value: Value| {
+            lua.create_any_userdata(this.label(widget_text_from_lua(lua, value)?))
+        });
+        reg.add_method("layer_id", |lua, this, () | {
+            lua.create_any_userdata(this.layer_id())
+        });
 
-    fn to_lua(value: Self, _lua: &Lua) -> Result<Value> {
-        let mut u = 0u8;
-        if value.click {
-            u &= 1;
-        }
-        if value.drag {
-            u &= 1 << 1;
-        }
-        if value.focusable {
-            u &= 1 << 2;
-        }
-        Ok(Value::Integer(u as _))
-    }
+        reg.add_method("layout", |lua, this, () | {
+            lua.create_any_userdata(this.layout().clone())
+        });
 
-    fn add_to_lua(lua: &Lua, egui_table: &Table) -> Result<()> {
-        let sense = lua.create_table()?;
-        sense.set("hover", Sense::to_lua(Sense::hover(), lua)?)?;
-        sense.set(
-            "focusable_noninteractive",
-            Sense::to_lua(Sense::focusable_noninteractive(), lua)?,
-        )?;
-        sense.set("click", Sense::to_lua(Sense::click(), lua)?)?;
-        sense.set("drag", Sense::to_lua(Sense::drag(), lua)?)?;
-        sense.set(
-            "union",
-            lua.create_function(|lua, (first, second): (Value, Value)| {
-                let first = Sense::from_lua(first)?;
-                let second = Sense::from_lua(second)?;
-                Sense::to_lua(first.union(second), lua)
-            })?,
-        )?;
-        sense.set(
-            "union",
-            lua.create_function(|_, value: Value| Ok(Sense::from_lua(value)?.interactive()))?,
-        )?;
+        reg.add_method_mut("link", |lua, this, value: Value| {
+            lua.create_any_userdata(this.link(widget_text_from_lua(lua, value)?))
+        });
 
-        egui_table.set("sense", sense)?;
-        Ok(())
-    }
-}
-impl LuaHelperTrait for Margin {
-    fn add_to_lua(lua: &Lua, egui_table: &Table) -> mlua::Result<()> {
-        let margin = lua.create_table()?;
-        margin.set(
-            "same",
-            lua.create_function(|lua, margin: f32| Margin::to_lua(Margin::same(margin), lua))?,
-        )?;
-        margin.set(
-            "symmetric",
-            lua.create_function(|lua, (x, y): (f32, f32)| {
-                Margin::to_lua(Margin::symmetric(x, y), lua)
-            })?,
-        )?;
-        margin.set(
-            "sum",
-            lua.create_function(|lua, value: Value| {
-                Vec2::to_lua(Margin::from_lua(value)?.sum(), lua)
-            })?,
-        )?;
-        margin.set(
-            "left_top",
-            lua.create_function(|lua, value: Value| {
-                Vec2::to_lua(Margin::from_lua(value)?.left_top(), lua)
+        reg.add_method_mut("make_persistent_id", |lua, this, value: Value| {
+            lua.create_any_userdata(this.make_persistent_id(LuaHashable::from_lua(value)?))
+        });
+
+        reg.add_method("max_rect", |lua, this, ()| {
+            Rect::to_lua(this.max_rect(), lua)
+        });
+
+        reg.add_method(
+            "memory",
+            |lua, this, add_contents:  Function| {
+                let ir = this.memory(  |reader| {
+                    lua.scope(|scope| {
+                        let reader = scope.create_any_userdata_ref(reader)?;
+                        let result: Result<MultiValue> = add_contents.call(reader);
+                        result
+                    })
+                });
+                ir
+            },
+        );
+        reg.add_method_mut(
+            "memory_mut",
+            |lua, this, add_contents:  Function| {
+                let ir = this.memory_mut(  |reader| {
+                    lua.scope(|scope| {
+                        let reader = scope.create_any_userdata_ref_mut(reader)?;
+                        let result: Result<MultiValue> = add_contents.call(reader);
+                        result
+                    })
+                });
+                ir
+            },
+        );
+        reg.add_method_mut(
+            "menu_button",
+            |lua, this, (title, add_contents): (Value, Function)| {
+                let ir = this.menu_button(widget_text_from_lua(lua, title)?, |ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_contents.call(ui);
+                        result
+                    })
+                });
+
+                let mut result = MultiValue::new();
+                let response = lua.create_any_userdata(ir.response)?;
+                result.push_front(Value::UserData(response));
+                if let Some(inner) = ir.inner {
+                    let inner = inner?;
+                    for v in inner {
+                        result.push_front(v);
+                    }
+                }
+                Ok(result)
+            },
+        );
+        reg.add_method_mut(
+            "menu_image_button",
+            |lua, this, (image, add_contents): (UserDataRef<Option<Image<'static>>>, Function)| {
+                show_menu_image_button(lua, this, &image, add_contents)
+            },
+        );
+        reg.add_method_mut(
+            "selectable_value",
+            |lua, this, (current_value, key, selected_value, text): (Table, Value, Value, Value)| {
+                let selected = current_value.get::<_, Value>(key.clone())?.equals(&selected_value)?;
+                let mut response = this.selectable_label(selected, widget_text_from_lua(lua, text)?);
+                if response.clicked() && !selected {
+                    current_value.set(key, selected_value)?;
+                    response.mark_changed();
+                }
+                lua.create_any_userdata(response)
+            },
+        );
+        reg.add_method_mut("selectable_label", |lua, ui, (selected, text): (bool, Value)| {
+            lua.create_any_userdata(ui.selectable_label(selected, widget_text_from_lua(lua, text)?))
+        });
+        reg.add_method("spacing", |lua, this, ()| {
+            lua.create_any_userdata(this.spacing().clone())
+        });
+        reg.add_method_mut("spacing_mut", |lua, this, add_contents: Function| {
+            lua.scope(|scope| {
+                let spacing = scope.create_any_userdata_ref_mut(this.spacing_mut())?;
+                let result: Result<MultiValue> = add_contents.call(spacing);
+                result
+            })
+        });
+        reg.add_method_mut(
+            "set_row_height",
+            |_, this, height: f32| {
+                Ok(this.set_row_height(height))
+            },
+        );
+        reg.add_method_mut(
+            "output",
+            |lua, this, add_contents:  Function| {
+                let ir = this.output(  |reader| {
+                    lua.scope(|scope| {
+                        let reader = scope.create_any_userdata_ref(reader)?;
+                        let result: Result<MultiValue> = add_contents.call(reader);
+                        result
+                    })
+                });
+                ir
+            },
+        );
+        reg.add_method_mut(
+            "output_mut",
+            |lua, this, add_contents:  Function| {
+                let ir = this.output_mut(  |reader| {
+                    lua.scope(|scope| {
+                        let reader = scope.create_any_userdata_ref_mut(reader)?;
+                        let result: Result<MultiValue> = add_contents.call(reader);
+                        result
+                    })
+                });
+                ir
+            },
+        );
+        reg.add_method(
+            "next_widget_position",
+            |lua, this, ()| {
+                Pos2::to_lua(this.next_widget_position(), lua)
+            },
+        );
+        reg.add_method(
+            "painter",
+            |lua, this, ()| {
+                lua.create_any_userdata(this.painter().clone())
+            },
+        );
+        reg.add_method_mut("put", |lua, this, (max_rect, widget): (Value, AnyUserData)| {
+            let max_rect = Rect::from_lua(max_rect)?;
+            lua.create_any_userdata(add_lua_widget(lua, widget, |w| this.put(max_rect, w))?)
+        });
+        reg.add_method_mut("text_edit_multiline", |lua, this, value: Table| {
+            let mut b: String = value.get("text")?;
+            let result = lua.create_any_userdata(this.text_edit_multiline(&mut b));
+            value.set("text", b)?;
+            result
+        });
+        reg.add_method_mut("text_edit_singleline", |lua, this, value: Table| {
+            let mut b: String = value.get("text")?;
+            let result = lua.create_any_userdata(this.text_edit_singleline(&mut b));
+            value.set("text", b)?;
+            result
+        });
+        reg.add_method_mut("set_enabled", |_, this, enabled: bool| {
+            this.set_enabled(enabled);
+            Ok(())
+        });
+
+        reg.add_method("wrap_text", |_, this, ()| Ok(this.wrap_text()));
+
+        reg.add_method_mut("set_clip_rect", |_, this, clip_rect: Value| {
+            this.set_clip_rect(Rect::from_lua(clip_rect)?);
+            Ok(())
+        });
+    })?;
+
+    Ok(())
+}
+impl LuaHelperTrait for AboveOrBelow {
+    fn add_to_lua(lua: &Lua, egui_table: &Table) -> Result<()> {
+        let above_or_below = lua.create_table()?;
+        above_or_below.set("above", Value::Integer(0))?;
+        above_or_below.set("below", Value::Integer(1))?;
+        egui_table.set("above_or_below", above_or_below)?;
+        Ok(())
+    }
+    fn from_lua(value: Value) -> Result<Self> {
+        Ok(match value {
+            Value::Integer(i) => match i {
+                0 => AboveOrBelow::Above,
+                1 => AboveOrBelow::Below,
+                _ => {
+                    return Err(mlua::Error::FromLuaConversionError {
+                        from: "luavalue",
+                        to: "aboveorbelow",
+                        message: Some("integer value out of range".to_string()),
+                    })
+                }
+            },
+            _ => {
+                return Err(mlua::Error::FromLuaConversionError {
+                    from: "luavalue",
+                    to: "aboveorbelow",
+                    message: None,
+                })
+            }
+        })
+    }
+
+    fn to_lua<'lua>(value: Self, _lua: &'lua Lua) -> Result<Value<'lua>> {
+        Ok(Value::Integer(match value {
+            AboveOrBelow::Above => 0,
+            AboveOrBelow::Below => 1,
+        }))
+    }
+}
+impl LuaHelperTrait for Sense {
+    fn from_lua(value: Value) -> Result<Self> {
+        match value {
+            Value::Integer(i) => {
+                let i = i as u8;
+                let click = 0 != (i & 1);
+                let drag = 0 != (i & (1 << 1));
+                let focusable = 0 != (i & (1 << 2));
+                Ok(Self {
+                    click,
+                    drag,
+                    focusable,
+                })
+            }
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: "luavalue",
+                to: "pointerbutton",
+                message: None,
+            }),
+        }
+    }
+
+    fn to_lua(value: Self, _lua: &Lua) -> Result<Value> {
+        let mut u = 0u8;
+        if value.click {
+            u |= 1;
+        }
+        if value.drag {
+            u |= 1 << 1;
+        }
+        if value.focusable {
+            u |= 1 << 2;
+        }
+        Ok(Value::Integer(u as _))
+    }
+
+    fn add_to_lua(lua: &Lua, egui_table: &Table) -> Result<()> {
+        let sense = lua.create_table()?;
+        sense.set("hover", Sense::to_lua(Sense::hover(), lua)?)?;
+        sense.set(
+            "focusable_noninteractive",
+            Sense::to_lua(Sense::focusable_noninteractive(), lua)?,
+        )?;
+        sense.set("click", Sense::to_lua(Sense::click(), lua)?)?;
+        sense.set("drag", Sense::to_lua(Sense::drag(), lua)?)?;
+        sense.set(
+            "union",
+            lua.create_function(|lua, (first, second): (Value, Value)| {
+                let first = Sense::from_lua(first)?;
+                let second = Sense::from_lua(second)?;
+                Sense::to_lua(first.union(second), lua)
+            })?,
+        )?;
+        sense.set(
+            "union",
+            lua.create_function(|_, value: Value| Ok(Sense::from_lua(value)?.interactive()))?,
+        )?;
+
+        egui_table.set("sense", sense)?;
+        Ok(())
+    }
+}
+impl LuaHelperTrait for Margin {
+    fn add_to_lua(lua: &Lua, egui_table: &Table) -> mlua::Result<()> {
+        let margin = lua.create_table()?;
+        margin.set(
+            "same",
+            lua.create_function(|lua, margin: f32| Margin::to_lua(Margin::same(margin), lua))?,
+        )?;
+        margin.set(
+            "symmetric",
+            lua.create_function(|lua, (x, y): (f32, f32)| {
+                Margin::to_lua(Margin::symmetric(x, y), lua)
+            })?,
+        )?;
+        margin.set(
+            "sum",
+            lua.create_function(|lua, value: Value| {
+                Vec2::to_lua(Margin::from_lua(value)?.sum(), lua)
+            })?,
+        )?;
+        margin.set(
+            "left_top",
+            lua.create_function(|lua, value: Value| {
+                Vec2::to_lua(Margin::from_lua(value)?.left_top(), lua)
             })?,
         )?;
         margin.set(
@@ -1130,6 +2323,13 @@ impl LuaHelperTrait for TextStyle {
         text_style.set("monospace", Value::Integer(2))?;
         text_style.set("button", Value::Integer(3))?;
         text_style.set("heading", Value::Integer(4))?;
+        // custom styles are plain strings, this only exists for discoverability
+        text_style.set(
+            "name",
+            lua.create_function(|lua, name: String| {
+                TextStyle::to_lua(TextStyle::Name(name.into()), lua)
+            })?,
+        )?;
         text_style.set_readonly(true);
         egui_table.set("text_style", text_style)?;
         Ok(())
@@ -1197,23 +2397,88 @@ impl LuaHelperTrait for Rounding {
 }
 impl LuaHelperTrait for Rect {
     fn add_to_lua(lua: &Lua, egui_table: &Table) -> mlua::Result<()> {
-        let rect = lua.create_table()?;
-        rect.set("everything", Rect::to_lua(Rect::EVERYTHING, lua)?)?;
-        rect.set("nothing", Rect::to_lua(Rect::NOTHING, lua)?)?;
-        rect.set("nan", Rect::to_lua(Rect::NAN, lua)?)?;
-        rect.set(
-            "from_min_max",
+        lua.register_userdata_type(|reg: &mut UserDataRegistry<Rect>| {
+            reg.add_field_method_get("min", |lua, this| Pos2::to_lua(this.min, lua));
+            reg.add_field_method_get("max", |lua, this| Pos2::to_lua(this.max, lua));
+            reg.add_field_method_set("min", |_, this, value: Value| {
+                this.min = Pos2::from_lua(value)?;
+                Ok(())
+            });
+            reg.add_field_method_set("max", |_, this, value: Value| {
+                this.max = Pos2::from_lua(value)?;
+                Ok(())
+            });
+
+            reg.add_method("width", |_, this, ()| Ok(this.width()));
+            reg.add_method("height", |_, this, ()| Ok(this.height()));
+            reg.add_method("size", |lua, this, ()| Vec2::to_lua(this.size(), lua));
+            reg.add_method("center", |lua, this, ()| Pos2::to_lua(this.center(), lua));
+            reg.add_method("contains", |_, this, pos: Value| {
+                Ok(this.contains(Pos2::from_lua(pos)?))
+            });
+            reg.add_method("intersects", |_, this, other: Value| {
+                Ok(this.intersects(Rect::from_lua(other)?))
+            });
+            reg.add_method("expand", |lua, this, amnt: f32| {
+                Rect::to_lua(this.expand(amnt), lua)
+            });
+            reg.add_method("shrink", |lua, this, amnt: f32| {
+                Rect::to_lua(this.shrink(amnt), lua)
+            });
+            reg.add_method("translate", |lua, this, amnt: Value| {
+                Rect::to_lua(this.translate(Vec2::from_lua(amnt)?), lua)
+            });
+            reg.add_method("union", |lua, this, other: Value| {
+                Rect::to_lua(this.union(Rect::from_lua(other)?), lua)
+            });
+            reg.add_method("intersect", |lua, this, other: Value| {
+                Rect::to_lua(this.intersect(Rect::from_lua(other)?), lua)
+            });
+            reg.add_method("lerp_inside", |lua, this, t: Value| {
+                Pos2::to_lua(this.lerp_inside(Vec2::from_lua(t)?), lua)
+            });
+            reg.add_meta_method(MetaMethod::Eq, |_, this, other: Value| {
+                Ok(*this == Rect::from_lua(other)?)
+            });
+            reg.add_meta_method(MetaMethod::ToString, |_, this, ()| Ok(format!("{this:?}")));
+        })?;
+        let rect = lua.create_table()?;
+        rect.set("everything", Rect::to_lua(Rect::EVERYTHING, lua)?)?;
+        rect.set("nothing", Rect::to_lua(Rect::NOTHING, lua)?)?;
+        rect.set("nan", Rect::to_lua(Rect::NAN, lua)?)?;
+        rect.set(
+            "from_min_max",
             lua.create_function(|lua, (min, max): (Value, Value)| {
                 let min = Pos2::from_lua(min)?;
                 let max = Pos2::from_lua(max)?;
                 Rect::to_lua(Rect { min, max }, lua)
             })?,
         )?;
-        egui_table.set("stroke", rect)?;
+        rect.set(
+            "from_min_size",
+            lua.create_function(|lua, (min, size): (Value, Value)| {
+                Rect::to_lua(
+                    Rect::from_min_size(Pos2::from_lua(min)?, Vec2::from_lua(size)?),
+                    lua,
+                )
+            })?,
+        )?;
+        rect.set(
+            "from_center_size",
+            lua.create_function(|lua, (center, size): (Value, Value)| {
+                Rect::to_lua(
+                    Rect::from_center_size(Pos2::from_lua(center)?, Vec2::from_lua(size)?),
+                    lua,
+                )
+            })?,
+        )?;
+        egui_table.set("rect", rect)?;
         Ok(())
     }
     fn from_lua(value: Value) -> Result<Self> {
         Ok(match value {
+            Value::UserData(u) => *u.borrow::<Rect>()?,
+            // tables with `min` and `max` fields are still accepted for older scripts
             Value::Table(t) => {
                 let min = Pos2::from_lua(t.get("min")?)?;
                 let max = Pos2::from_lua(t.get("max")?)?;
@@ -1222,7 +2487,7 @@ impl LuaHelperTrait for Rect {
             _ => {
                 return Err(mlua::Error::FromLuaConversionError {
                     from: "luavalue",
-                    to: "pointerbutton",
+                    to: "rect",
                     message: None,
                 })
             }
@@ -1230,10 +2495,7 @@ impl LuaHelperTrait for Rect {
     }
 
     fn to_lua(value: Self, lua: &Lua) -> Result<Value> {
-        let rect = lua.create_table()?;
-        rect.set("min", Pos2::to_lua(value.min, lua)?)?;
-        rect.set("max", Pos2::to_lua(value.max, lua)?)?;
-        Ok(Value::Table(rect))
+        lua.create_any_userdata(value).map(Value::UserData)
     }
 }
 impl LuaHelperTrait for Color32 {
@@ -1273,6 +2535,71 @@ impl LuaHelperTrait for Color32 {
                 Color32::to_lua(Color32::from_rgba_premultiplied(r, g, b, a), lua)
             })?,
         )?;
+        color32.set(
+            "from_rgba_unmultiplied",
+            lua.create_function(|lua, (r, g, b, a): (u8, u8, u8, u8)| {
+                Color32::to_lua(Color32::from_rgba_unmultiplied(r, g, b, a), lua)
+            })?,
+        )?;
+        color32.set(
+            "from_rgb",
+            lua.create_function(|lua, (r, g, b): (u8, u8, u8)| {
+                Color32::to_lua(Color32::from_rgb(r, g, b), lua)
+            })?,
+        )?;
+        color32.set(
+            "from_gray",
+            lua.create_function(|lua, l: u8| Color32::to_lua(Color32::from_gray(l), lua))?,
+        )?;
+        color32.set(
+            "from_hex",
+            lua.create_function(|lua, hex: String| {
+                let color = Color32::from_hex(&hex).map_err(|e| {
+                    mlua::Error::RuntimeError(format!("invalid hex color {hex}: {e:?}"))
+                })?;
+                Color32::to_lua(color, lua)
+            })?,
+        )?;
+        color32.set(
+            "to_hex",
+            lua.create_function(|_, color: Value| Ok(Color32::from_lua(color)?.to_hex()))?,
+        )?;
+        color32.set(
+            "r",
+            lua.create_function(|_, color: Value| Ok(Color32::from_lua(color)?.r()))?,
+        )?;
+        color32.set(
+            "g",
+            lua.create_function(|_, color: Value| Ok(Color32::from_lua(color)?.g()))?,
+        )?;
+        color32.set(
+            "b",
+            lua.create_function(|_, color: Value| Ok(Color32::from_lua(color)?.b()))?,
+        )?;
+        color32.set(
+            "a",
+            lua.create_function(|_, color: Value| Ok(Color32::from_lua(color)?.a()))?,
+        )?;
+        color32.set(
+            "gamma_multiply",
+            lua.create_function(|lua, (color, factor): (Value, f32)| {
+                Color32::to_lua(Color32::from_lua(color)?.gamma_multiply(factor), lua)
+            })?,
+        )?;
+        color32.set(
+            "linear_multiply",
+            lua.create_function(|lua, (color, factor): (Value, f32)| {
+                Color32::to_lua(Color32::from_lua(color)?.linear_multiply(factor), lua)
+            })?,
+        )?;
+        color32.set(
+            "lerp",
+            lua.create_function(|lua, (from, to, t): (Value, Value, f32)| {
+                let from = Rgba::from(Color32::from_lua(from)?);
+                let to = Rgba::from(Color32::from_lua(to)?);
+                Color32::to_lua(egui::lerp(from..=to, t).into(), lua)
+            })?,
+        )?;
         egui_table.set("color32", color32)?;
         Ok(())
     }
@@ -1301,6 +2628,116 @@ impl LuaHelperTrait for Color32 {
         }))
     }
 }
+impl LuaHelperTrait for Rgba {
+    fn add_to_lua(lua: &Lua, egui_table: &Table) -> mlua::Result<()> {
+        let rgba = lua.create_table()?;
+        rgba.set(
+            "from_rgba_premultiplied",
+            lua.create_function(|lua, (r, g, b, a): (f32, f32, f32, f32)| {
+                Rgba::to_lua(Rgba::from_rgba_premultiplied(r, g, b, a), lua)
+            })?,
+        )?;
+        rgba.set(
+            "from_rgba_unmultiplied",
+            lua.create_function(|lua, (r, g, b, a): (f32, f32, f32, f32)| {
+                Rgba::to_lua(Rgba::from_rgba_unmultiplied(r, g, b, a), lua)
+            })?,
+        )?;
+        rgba.set(
+            "from_color32",
+            lua.create_function(|lua, color: Value| {
+                Rgba::to_lua(Color32::from_lua(color)?.into(), lua)
+            })?,
+        )?;
+        rgba.set(
+            "to_color32",
+            lua.create_function(|lua, rgba: Value| {
+                Color32::to_lua(Rgba::from_lua(rgba)?.into(), lua)
+            })?,
+        )?;
+        egui_table.set("rgba", rgba)?;
+        Ok(())
+    }
+    fn from_lua(value: Value) -> Result<Self> {
+        Ok(match value {
+            Value::Table(t) => {
+                let r: f32 = t.get("r")?;
+                let g: f32 = t.get("g")?;
+                let b: f32 = t.get("b")?;
+                let a: f32 = t.get("a")?;
+                Rgba::from_rgba_premultiplied(r, g, b, a)
+            }
+            _ => {
+                return Err(mlua::Error::FromLuaConversionError {
+                    from: "luavalue",
+                    to: "rgba",
+                    message: None,
+                })
+            }
+        })
+    }
+
+    fn to_lua<'lua>(value: Self, lua: &'lua Lua) -> Result<Value<'lua>> {
+        let rgba = lua.create_table()?;
+        rgba.set("r", value.r())?;
+        rgba.set("g", value.g())?;
+        rgba.set("b", value.b())?;
+        rgba.set("a", value.a())?;
+        Ok(Value::Table(rgba))
+    }
+}
+impl LuaHelperTrait for Hsva {
+    fn add_to_lua(lua: &Lua, egui_table: &Table) -> mlua::Result<()> {
+        let hsva = lua.create_table()?;
+        hsva.set(
+            "new",
+            lua.create_function(|lua, (h, s, v, a): (f32, f32, f32, f32)| {
+                Hsva::to_lua(Hsva::new(h, s, v, a), lua)
+            })?,
+        )?;
+        hsva.set(
+            "from_color32",
+            lua.create_function(|lua, color: Value| {
+                Hsva::to_lua(Color32::from_lua(color)?.into(), lua)
+            })?,
+        )?;
+        hsva.set(
+            "to_color32",
+            lua.create_function(|lua, hsva: Value| {
+                Color32::to_lua(Hsva::from_lua(hsva)?.into(), lua)
+            })?,
+        )?;
+        egui_table.set("hsva", hsva)?;
+        Ok(())
+    }
+    fn from_lua(value: Value) -> Result<Self> {
+        Ok(match value {
+            Value::Table(t) => {
+                let h: f32 = t.get("h")?;
+                let s: f32 = t.get("s")?;
+                let v: f32 = t.get("v")?;
+                let a: f32 = t.get("a")?;
+                Hsva { h, s, v, a }
+            }
+            _ => {
+                return Err(mlua::Error::FromLuaConversionError {
+                    from: "luavalue",
+                    to: "hsva",
+                    message: None,
+                })
+            }
+        })
+    }
+
+    fn to_lua<'lua>(value: Self, lua: &'lua Lua) -> Result<Value<'lua>> {
+        let hsva = lua.create_table()?;
+        hsva.set("h", value.h)?;
+        hsva.set("s", value.s)?;
+        hsva.set("v", value.v)?;
+        hsva.set("a", value.a)?;
+        Ok(Value::Table(hsva))
+    }
+}
 impl LuaHelperTrait for Pos2 {
     fn from_lua(value: Value) -> Result<Self> {
         match value {
@@ -1317,9 +2754,60 @@ impl LuaHelperTrait for Pos2 {
         Ok(Value::Vector(Vector::new(value.x, value.y, 0.0, 0.0)))
     }
 
-    fn add_to_lua(_lua: &Lua, _egui_table: &Table) -> Result<()> {
+    fn add_to_lua(lua: &Lua, egui_table: &Table) -> Result<()> {
+        let pos2 = lua.create_table()?;
+        pos2.set(
+            "new",
+            lua.create_function(|lua, (x, y): (f32, f32)| Pos2::to_lua(Pos2::new(x, y), lua))?,
+        )?;
+        pos2.set("zero", Pos2::to_lua(Pos2::ZERO, lua)?)?;
+        pos2.set(
+            "distance",
+            lua.create_function(|_, (a, b): (Value, Value)| {
+                Ok(Pos2::from_lua(a)?.distance(Pos2::from_lua(b)?))
+            })?,
+        )?;
+        egui_table.set("pos2", pos2)?;
+        Ok(())
+    }
+}
+impl LuaHelperTrait for SliderOrientation {
+    fn add_to_lua(lua: &Lua, egui_table: &Table) -> Result<()> {
+        let slider_orientation = lua.create_table()?;
+        slider_orientation.set("horizontal", Value::Integer(0))?;
+        slider_orientation.set("vertical", Value::Integer(1))?;
+        egui_table.set("slider_orientation", slider_orientation)?;
         Ok(())
     }
+    fn from_lua(value: Value) -> Result<Self> {
+        Ok(match value {
+            Value::Integer(i) => match i {
+                0 => SliderOrientation::Horizontal,
+                1 => SliderOrientation::Vertical,
+                _ => {
+                    return Err(mlua::Error::FromLuaConversionError {
+                        from: "luavalue",
+                        to: "sliderorientation",
+                        message: Some("integer value out of range".to_string()),
+                    })
+                }
+            },
+            _ => {
+                return Err(mlua::Error::FromLuaConversionError {
+                    from: "luavalue",
+                    to: "sliderorientation",
+                    message: None,
+                })
+            }
+        })
+    }
+
+    fn to_lua<'lua>(value: Self, _lua: &'lua Lua) -> Result<Value<'lua>> {
+        Ok(Value::Integer(match value {
+            SliderOrientation::Horizontal => 0,
+            SliderOrientation::Vertical => 1,
+        }))
+    }
 }
 impl LuaHelperTrait for Stroke {
     fn add_to_lua(lua: &Lua, egui_table: &Table) -> mlua::Result<()> {
@@ -1375,7 +2863,34 @@ impl LuaHelperTrait for Vec2 {
         Ok(Value::Vector(Vector::new(value.x, value.y, 0.0, 0.0)))
     }
 
-    fn add_to_lua(_lua: &Lua, _egui_table: &Table) -> Result<()> {
+    fn add_to_lua(lua: &Lua, egui_table: &Table) -> Result<()> {
+        let vec2 = lua.create_table()?;
+        vec2.set(
+            "new",
+            lua.create_function(|lua, (x, y): (f32, f32)| Vec2::to_lua(Vec2::new(x, y), lua))?,
+        )?;
+        vec2.set(
+            "splat",
+            lua.create_function(|lua, v: f32| Vec2::to_lua(Vec2::splat(v), lua))?,
+        )?;
+        vec2.set("zero", Vec2::to_lua(Vec2::ZERO, lua)?)?;
+        vec2.set(
+            "length",
+            lua.create_function(|_, v: Value| Ok(Vec2::from_lua(v)?.length()))?,
+        )?;
+        vec2.set(
+            "normalized",
+            lua.create_function(|lua, v: Value| {
+                Vec2::to_lua(Vec2::from_lua(v)?.normalized(), lua)
+            })?,
+        )?;
+        vec2.set(
+            "dot",
+            lua.create_function(|_, (a, b): (Value, Value)| {
+                Ok(Vec2::from_lua(a)?.dot(Vec2::from_lua(b)?))
+            })?,
+        )?;
+        egui_table.set("vec2", vec2)?;
         Ok(())
     }
 }
@@ -1556,144 +3071,3160 @@ impl LuaHelperTrait for Direction {
                         to: "pointerbutton",
                         message: Some("integer value out of range".to_string()),
                     })
-                }
+                }
+            },
+            _ => {
+                return Err(mlua::Error::FromLuaConversionError {
+                    from: "luavalue",
+                    to: "pointerbutton",
+                    message: None,
+                })
+            }
+        })
+    }
+
+    fn to_lua(value: Self, _lua: &Lua) -> Result<Value> {
+        Ok(Value::Integer(match value {
+            Self::LeftToRight => 0,
+            Self::RightToLeft => 1,
+            Self::TopDown => 2,
+            Self::BottomUp => 3,
+        }))
+    }
+}
+impl LuaHelperTrait for CursorIcon {
+    fn add_to_lua(lua: &Lua, egui_table: &Table) -> Result<()> {
+        let cursor_icon = lua.create_table()?;
+        cursor_icon.set("default", Value::Integer(0))?;
+        cursor_icon.set("none", Value::Integer(1))?;
+        cursor_icon.set("context_menu", Value::Integer(2))?;
+        cursor_icon.set("help", Value::Integer(3))?;
+        cursor_icon.set("pointing_hand", Value::Integer(4))?;
+        cursor_icon.set("progress", Value::Integer(5))?;
+        cursor_icon.set("wait", Value::Integer(6))?;
+        cursor_icon.set("cell", Value::Integer(7))?;
+        cursor_icon.set("crosshair", Value::Integer(8))?;
+        cursor_icon.set("text", Value::Integer(9))?;
+        cursor_icon.set("vertical_text", Value::Integer(10))?;
+        cursor_icon.set("alias", Value::Integer(11))?;
+        cursor_icon.set("copy", Value::Integer(12))?;
+        cursor_icon.set("move", Value::Integer(13))?;
+        cursor_icon.set("no_drop", Value::Integer(14))?;
+        cursor_icon.set("not_allowed", Value::Integer(15))?;
+        cursor_icon.set("grab", Value::Integer(16))?;
+        cursor_icon.set("grabbing", Value::Integer(17))?;
+        cursor_icon.set("all_scroll", Value::Integer(18))?;
+        cursor_icon.set("resize_horizontal", Value::Integer(19))?;
+        cursor_icon.set("resize_ne_sw", Value::Integer(20))?;
+        cursor_icon.set("resize_nw_se", Value::Integer(21))?;
+        cursor_icon.set("resize_vertical", Value::Integer(22))?;
+        cursor_icon.set("resize_east", Value::Integer(23))?;
+        cursor_icon.set("resize_south_east", Value::Integer(24))?;
+        cursor_icon.set("resize_south", Value::Integer(25))?;
+        cursor_icon.set("resize_south_west", Value::Integer(26))?;
+        cursor_icon.set("resize_west", Value::Integer(27))?;
+        cursor_icon.set("resize_north_west", Value::Integer(28))?;
+        cursor_icon.set("resize_north", Value::Integer(29))?;
+        cursor_icon.set("resize_north_east", Value::Integer(30))?;
+        cursor_icon.set("resize_column", Value::Integer(31))?;
+        cursor_icon.set("resize_row", Value::Integer(32))?;
+        cursor_icon.set("zoom_in", Value::Integer(33))?;
+        cursor_icon.set("zoom_out", Value::Integer(34))?;
+        egui_table.set("cursor_icon", cursor_icon)?;
+        Ok(())
+    }
+    fn from_lua(value: Value) -> Result<Self> {
+        Ok(match value {
+            Value::Integer(i) => match i {
+                0 => CursorIcon::Default,
+                1 => CursorIcon::None,
+                2 => CursorIcon::ContextMenu,
+                3 => CursorIcon::Help,
+                4 => CursorIcon::PointingHand,
+                5 => CursorIcon::Progress,
+                6 => CursorIcon::Wait,
+                7 => CursorIcon::Cell,
+                8 => CursorIcon::Crosshair,
+                9 => CursorIcon::Text,
+                10 => CursorIcon::VerticalText,
+                11 => CursorIcon::Alias,
+                12 => CursorIcon::Copy,
+                13 => CursorIcon::Move,
+                14 => CursorIcon::NoDrop,
+                15 => CursorIcon::NotAllowed,
+                16 => CursorIcon::Grab,
+                17 => CursorIcon::Grabbing,
+                18 => CursorIcon::AllScroll,
+                19 => CursorIcon::ResizeHorizontal,
+                20 => CursorIcon::ResizeNeSw,
+                21 => CursorIcon::ResizeNwSe,
+                22 => CursorIcon::ResizeVertical,
+                23 => CursorIcon::ResizeEast,
+                24 => CursorIcon::ResizeSouthEast,
+                25 => CursorIcon::ResizeSouth,
+                26 => CursorIcon::ResizeSouthWest,
+                27 => CursorIcon::ResizeWest,
+                28 => CursorIcon::ResizeNorthWest,
+                29 => CursorIcon::ResizeNorth,
+                30 => CursorIcon::ResizeNorthEast,
+                31 => CursorIcon::ResizeColumn,
+                32 => CursorIcon::ResizeRow,
+                33 => CursorIcon::ZoomIn,
+                34 => CursorIcon::ZoomOut,
+                _ => {
+                    return Err(mlua::Error::FromLuaConversionError {
+                        from: "luavalue",
+                        to: "cursoricon",
+                        message: Some("integer value out of range".to_string()),
+                    })
+                }
+            },
+            _ => {
+                return Err(mlua::Error::FromLuaConversionError {
+                    from: "luavalue",
+                    to: "cursoricon",
+                    message: None,
+                })
+            }
+        })
+    }
+
+    fn to_lua<'lua>(value: Self, _lua: &'lua Lua) -> Result<Value<'lua>> {
+        Ok(Value::Integer(match value {
+            CursorIcon::Default => 0,
+            CursorIcon::None => 1,
+            CursorIcon::ContextMenu => 2,
+            CursorIcon::Help => 3,
+            CursorIcon::PointingHand => 4,
+            CursorIcon::Progress => 5,
+            CursorIcon::Wait => 6,
+            CursorIcon::Cell => 7,
+            CursorIcon::Crosshair => 8,
+            CursorIcon::Text => 9,
+            CursorIcon::VerticalText => 10,
+            CursorIcon::Alias => 11,
+            CursorIcon::Copy => 12,
+            CursorIcon::Move => 13,
+            CursorIcon::NoDrop => 14,
+            CursorIcon::NotAllowed => 15,
+            CursorIcon::Grab => 16,
+            CursorIcon::Grabbing => 17,
+            CursorIcon::AllScroll => 18,
+            CursorIcon::ResizeHorizontal => 19,
+            CursorIcon::ResizeNeSw => 20,
+            CursorIcon::ResizeNwSe => 21,
+            CursorIcon::ResizeVertical => 22,
+            CursorIcon::ResizeEast => 23,
+            CursorIcon::ResizeSouthEast => 24,
+            CursorIcon::ResizeSouth => 25,
+            CursorIcon::ResizeSouthWest => 26,
+            CursorIcon::ResizeWest => 27,
+            CursorIcon::ResizeNorthWest => 28,
+            CursorIcon::ResizeNorth => 29,
+            CursorIcon::ResizeNorthEast => 30,
+            CursorIcon::ResizeColumn => 31,
+            CursorIcon::ResizeRow => 32,
+            CursorIcon::ZoomIn => 33,
+            CursorIcon::ZoomOut => 34,
+        }))
+    }
+}
+impl LuaHelperTrait for FontFamily {
+    fn add_to_lua(lua: &Lua, egui_table: &Table) -> Result<()> {
+        let font_family = lua.create_table()?;
+        font_family.set("proportional", Value::Integer(0))?;
+        font_family.set("monospace", Value::Integer(1))?;
+        egui_table.set("font_family", font_family)?;
+        Ok(())
+    }
+    fn from_lua(value: Value) -> Result<Self> {
+        Ok(match value {
+            Value::Integer(i) => match i {
+                0 => FontFamily::Proportional,
+                1 => FontFamily::Monospace,
+                _ => {
+                    return Err(mlua::Error::FromLuaConversionError {
+                        from: "luavalue",
+                        to: "fontfamily",
+                        message: Some("integer value out of range".to_string()),
+                    })
+                }
+            },
+            Value::String(s) => FontFamily::Name(s.to_str()?.into()),
+            _ => {
+                return Err(mlua::Error::FromLuaConversionError {
+                    from: "luavalue",
+                    to: "fontfamily",
+                    message: None,
+                })
+            }
+        })
+    }
+
+    fn to_lua<'lua>(value: Self, lua: &'lua Lua) -> Result<Value<'lua>> {
+        Ok(match value {
+            FontFamily::Proportional => Value::Integer(0),
+            FontFamily::Monospace => Value::Integer(1),
+            FontFamily::Name(n) => Value::String(lua.create_string(n.as_bytes())?),
+        })
+    }
+}
+impl LuaHelperTrait for FontId {
+    fn from_lua(value: Value) -> Result<Self> {
+        Ok(match value {
+            Value::Table(t) => {
+                let size: f32 = t.get("size")?;
+                let family = FontFamily::from_lua(t.get("family")?)?;
+                FontId { size, family }
+            }
+            _ => {
+                return Err(mlua::Error::FromLuaConversionError {
+                    from: "luavalue",
+                    to: "fontid",
+                    message: None,
+                })
+            }
+        })
+    }
+
+    fn to_lua<'lua>(value: Self, lua: &'lua Lua) -> Result<Value<'lua>> {
+        let font_id = lua.create_table()?;
+        font_id.set("size", value.size)?;
+        font_id.set("family", FontFamily::to_lua(value.family, lua)?)?;
+        Ok(Value::Table(font_id))
+    }
+
+    fn add_to_lua(lua: &Lua, egui_table: &Table) -> Result<()> {
+        let font_id = lua.create_table()?;
+        font_id.set(
+            "new",
+            lua.create_function(|lua, (size, family): (f32, Value)| {
+                FontId::to_lua(FontId::new(size, FontFamily::from_lua(family)?), lua)
+            })?,
+        )?;
+        font_id.set(
+            "proportional",
+            lua.create_function(|lua, size: f32| FontId::to_lua(FontId::proportional(size), lua))?,
+        )?;
+        font_id.set(
+            "monospace",
+            lua.create_function(|lua, size: f32| FontId::to_lua(FontId::monospace(size), lua))?,
+        )?;
+        font_id.set(
+            "default",
+            lua.create_function(|lua, ()| FontId::to_lua(FontId::default(), lua))?,
+        )?;
+        egui_table.set("font_id", font_id)?;
+        Ok(())
+    }
+}
+impl LuaHelperTrait for TextureOptions {
+    fn from_lua(value: Value) -> Result<Self> {
+        Ok(match value {
+            Value::Nil => TextureOptions::default(),
+            Value::Table(t) => {
+                let mut options = TextureOptions::default();
+                if let Some(filter) = t.get::<_, Option<i32>>("magnification")? {
+                    options.magnification = texture_filter_from_lua(filter)?;
+                }
+                if let Some(filter) = t.get::<_, Option<i32>>("minification")? {
+                    options.minification = texture_filter_from_lua(filter)?;
+                }
+                if let Some(wrap_mode) = t.get::<_, Option<i32>>("wrap_mode")? {
+                    options.wrap_mode = match wrap_mode {
+                        0 => TextureWrapMode::ClampToEdge,
+                        1 => TextureWrapMode::Repeat,
+                        2 => TextureWrapMode::MirroredRepeat,
+                        _ => {
+                            return Err(mlua::Error::FromLuaConversionError {
+                                from: "luavalue",
+                                to: "texturewrapmode",
+                                message: Some("integer value out of range".to_string()),
+                            })
+                        }
+                    };
+                }
+                options
+            }
+            _ => {
+                return Err(mlua::Error::FromLuaConversionError {
+                    from: "luavalue",
+                    to: "textureoptions",
+                    message: None,
+                })
+            }
+        })
+    }
+
+    fn to_lua<'lua>(value: Self, lua: &'lua Lua) -> Result<Value<'lua>> {
+        let options = lua.create_table()?;
+        options.set("magnification", texture_filter_to_lua(value.magnification))?;
+        options.set("minification", texture_filter_to_lua(value.minification))?;
+        options.set(
+            "wrap_mode",
+            match value.wrap_mode {
+                TextureWrapMode::ClampToEdge => 0,
+                TextureWrapMode::Repeat => 1,
+                TextureWrapMode::MirroredRepeat => 2,
+            },
+        )?;
+        Ok(Value::Table(options))
+    }
+
+    fn add_to_lua(lua: &Lua, egui_table: &Table) -> Result<()> {
+        let texture_filter = lua.create_table()?;
+        texture_filter.set("nearest", Value::Integer(0))?;
+        texture_filter.set("linear", Value::Integer(1))?;
+        egui_table.set("texture_filter", texture_filter)?;
+        let texture_wrap_mode = lua.create_table()?;
+        texture_wrap_mode.set("clamp_to_edge", Value::Integer(0))?;
+        texture_wrap_mode.set("repeat", Value::Integer(1))?;
+        texture_wrap_mode.set("mirrored_repeat", Value::Integer(2))?;
+        egui_table.set("texture_wrap_mode", texture_wrap_mode)?;
+        let texture_options = lua.create_table()?;
+        texture_options.set(
+            "linear",
+            TextureOptions::to_lua(TextureOptions::LINEAR, lua)?,
+        )?;
+        texture_options.set(
+            "nearest",
+            TextureOptions::to_lua(TextureOptions::NEAREST, lua)?,
+        )?;
+        texture_options.set(
+            "linear_repeat",
+            TextureOptions::to_lua(TextureOptions::LINEAR_REPEAT, lua)?,
+        )?;
+        texture_options.set(
+            "linear_mirrored_repeat",
+            TextureOptions::to_lua(TextureOptions::LINEAR_MIRRORED_REPEAT, lua)?,
+        )?;
+        texture_options.set(
+            "nearest_repeat",
+            TextureOptions::to_lua(TextureOptions::NEAREST_REPEAT, lua)?,
+        )?;
+        texture_options.set(
+            "nearest_mirrored_repeat",
+            TextureOptions::to_lua(TextureOptions::NEAREST_MIRRORED_REPEAT, lua)?,
+        )?;
+        egui_table.set("texture_options", texture_options)?;
+        Ok(())
+    }
+}
+fn texture_filter_from_lua(value: i32) -> Result<TextureFilter> {
+    Ok(match value {
+        0 => TextureFilter::Nearest,
+        1 => TextureFilter::Linear,
+        _ => {
+            return Err(mlua::Error::FromLuaConversionError {
+                from: "luavalue",
+                to: "texturefilter",
+                message: Some("integer value out of range".to_string()),
+            })
+        }
+    })
+}
+fn texture_filter_to_lua(value: TextureFilter) -> i32 {
+    match value {
+        TextureFilter::Nearest => 0,
+        TextureFilter::Linear => 1,
+    }
+}
+impl LuaHelperTrait for TextFormat {
+    fn from_lua(value: Value) -> Result<Self> {
+        Ok(match value {
+            Value::Table(t) => {
+                let mut format = TextFormat::default();
+                let font_id: Value = t.get("font_id")?;
+                if !font_id.is_nil() {
+                    format.font_id = FontId::from_lua(font_id)?;
+                }
+                if let Some(spacing) = t.get::<_, Option<f32>>("extra_letter_spacing")? {
+                    format.extra_letter_spacing = spacing;
+                }
+                format.line_height = t.get("line_height")?;
+                let color: Value = t.get("color")?;
+                if !color.is_nil() {
+                    format.color = Color32::from_lua(color)?;
+                }
+                let background: Value = t.get("background")?;
+                if !background.is_nil() {
+                    format.background = Color32::from_lua(background)?;
+                }
+                format.italics = t.get::<_, Option<bool>>("italics")?.unwrap_or_default();
+                let underline: Value = t.get("underline")?;
+                if !underline.is_nil() {
+                    format.underline = Stroke::from_lua(underline)?;
+                }
+                let strikethrough: Value = t.get("strikethrough")?;
+                if !strikethrough.is_nil() {
+                    format.strikethrough = Stroke::from_lua(strikethrough)?;
+                }
+                let valign: Value = t.get("valign")?;
+                if !valign.is_nil() {
+                    format.valign = Align::from_lua(valign)?;
+                }
+                format
+            }
+            _ => {
+                return Err(mlua::Error::FromLuaConversionError {
+                    from: "luavalue",
+                    to: "textformat",
+                    message: None,
+                })
+            }
+        })
+    }
+
+    fn to_lua<'lua>(value: Self, lua: &'lua Lua) -> Result<Value<'lua>> {
+        let format = lua.create_table()?;
+        format.set("font_id", FontId::to_lua(value.font_id, lua)?)?;
+        format.set("extra_letter_spacing", value.extra_letter_spacing)?;
+        format.set("line_height", value.line_height)?;
+        format.set("color", Color32::to_lua(value.color, lua)?)?;
+        format.set("background", Color32::to_lua(value.background, lua)?)?;
+        format.set("italics", value.italics)?;
+        format.set("underline", Stroke::to_lua(value.underline, lua)?)?;
+        format.set("strikethrough", Stroke::to_lua(value.strikethrough, lua)?)?;
+        format.set("valign", Align::to_lua(value.valign, lua)?)?;
+        Ok(Value::Table(format))
+    }
+
+    fn add_to_lua(lua: &Lua, egui_table: &Table) -> Result<()> {
+        let text_format = lua.create_table()?;
+        text_format.set(
+            "default",
+            lua.create_function(|lua, ()| TextFormat::to_lua(TextFormat::default(), lua))?,
+        )?;
+        text_format.set(
+            "simple",
+            lua.create_function(|lua, (font_id, color): (Value, Value)| {
+                TextFormat::to_lua(
+                    TextFormat::simple(FontId::from_lua(font_id)?, Color32::from_lua(color)?),
+                    lua,
+                )
+            })?,
+        )?;
+        egui_table.set("text_format", text_format)?;
+        Ok(())
+    }
+}
+impl LuaHelperTrait for TextWrapping {
+    fn from_lua(value: Value) -> Result<Self> {
+        Ok(match value {
+            Value::Table(t) => {
+                let mut wrapping = TextWrapping::default();
+                if let Some(max_width) = t.get::<_, Option<f32>>("max_width")? {
+                    wrapping.max_width = max_width;
+                }
+                // lua numbers can't hold `usize::MAX`, so this saturates
+                if let Some(max_rows) = t.get::<_, Option<f64>>("max_rows")? {
+                    wrapping.max_rows = max_rows as usize;
+                }
+                if let Some(break_anywhere) = t.get::<_, Option<bool>>("break_anywhere")? {
+                    wrapping.break_anywhere = break_anywhere;
+                }
+                let overflow_character: Value = t.get("overflow_character")?;
+                wrapping.overflow_character = match overflow_character {
+                    Value::Nil => wrapping.overflow_character,
+                    Value::Boolean(false) => None,
+                    Value::String(s) => s.to_str()?.chars().next(),
+                    _ => {
+                        return Err(mlua::Error::FromLuaConversionError {
+                            from: "luavalue",
+                            to: "overflow_character",
+                            message: Some("expected a string or false".to_string()),
+                        })
+                    }
+                };
+                wrapping
+            }
+            _ => {
+                return Err(mlua::Error::FromLuaConversionError {
+                    from: "luavalue",
+                    to: "textwrapping",
+                    message: None,
+                })
+            }
+        })
+    }
+
+    fn to_lua<'lua>(value: Self, lua: &'lua Lua) -> Result<Value<'lua>> {
+        let wrapping = lua.create_table()?;
+        wrapping.set("max_width", value.max_width)?;
+        wrapping.set("max_rows", value.max_rows as f64)?;
+        wrapping.set("break_anywhere", value.break_anywhere)?;
+        match value.overflow_character {
+            Some(c) => wrapping.set("overflow_character", c.to_string())?,
+            None => wrapping.set("overflow_character", false)?,
+        }
+        Ok(Value::Table(wrapping))
+    }
+
+    fn add_to_lua(lua: &Lua, egui_table: &Table) -> Result<()> {
+        let text_wrapping = lua.create_table()?;
+        text_wrapping.set(
+            "default",
+            lua.create_function(|lua, ()| TextWrapping::to_lua(TextWrapping::default(), lua))?,
+        )?;
+        text_wrapping.set(
+            "no_max_width",
+            lua.create_function(|lua, ()| TextWrapping::to_lua(TextWrapping::no_max_width(), lua))?,
+        )?;
+        text_wrapping.set(
+            "truncate_at_width",
+            lua.create_function(|lua, max_width: f32| {
+                TextWrapping::to_lua(TextWrapping::truncate_at_width(max_width), lua)
+            })?,
+        )?;
+        egui_table.set("text_wrapping", text_wrapping)?;
+        Ok(())
+    }
+}
+impl LuaHelperTrait for Key {
+    fn add_to_lua(lua: &Lua, egui_table: &Table) -> Result<()> {
+        let key = lua.create_table()?;
+        key.set("arrow_down", Value::Integer(0))?;
+        key.set("arrow_left", Value::Integer(1))?;
+        key.set("arrow_right", Value::Integer(2))?;
+        key.set("arrow_up", Value::Integer(3))?;
+        key.set("escape", Value::Integer(4))?;
+        key.set("tab", Value::Integer(5))?;
+        key.set("backspace", Value::Integer(6))?;
+        key.set("enter", Value::Integer(7))?;
+        key.set("insert", Value::Integer(8))?;
+        key.set("delete", Value::Integer(9))?;
+        key.set("home", Value::Integer(10))?;
+        key.set("end", Value::Integer(11))?;
+        key.set("page_up", Value::Integer(12))?;
+        key.set("page_down", Value::Integer(13))?;
+        key.set("copy", Value::Integer(14))?;
+        key.set("cut", Value::Integer(15))?;
+        key.set("paste", Value::Integer(16))?;
+        key.set("space", Value::Integer(17))?;
+        key.set("colon", Value::Integer(18))?;
+        key.set("comma", Value::Integer(19))?;
+        key.set("minus", Value::Integer(20))?;
+        key.set("period", Value::Integer(21))?;
+        key.set("plus", Value::Integer(22))?;
+        key.set("equals", Value::Integer(23))?;
+        key.set("semicolon", Value::Integer(24))?;
+        key.set("open_bracket", Value::Integer(25))?;
+        key.set("close_bracket", Value::Integer(26))?;
+        key.set("backtick", Value::Integer(27))?;
+        key.set("backslash", Value::Integer(28))?;
+        key.set("slash", Value::Integer(29))?;
+        key.set("pipe", Value::Integer(30))?;
+        key.set("questionmark", Value::Integer(31))?;
+        key.set("num0", Value::Integer(32))?;
+        key.set("num1", Value::Integer(33))?;
+        key.set("num2", Value::Integer(34))?;
+        key.set("num3", Value::Integer(35))?;
+        key.set("num4", Value::Integer(36))?;
+        key.set("num5", Value::Integer(37))?;
+        key.set("num6", Value::Integer(38))?;
+        key.set("num7", Value::Integer(39))?;
+        key.set("num8", Value::Integer(40))?;
+        key.set("num9", Value::Integer(41))?;
+        key.set("a", Value::Integer(42))?;
+        key.set("b", Value::Integer(43))?;
+        key.set("c", Value::Integer(44))?;
+        key.set("d", Value::Integer(45))?;
+        key.set("e", Value::Integer(46))?;
+        key.set("f", Value::Integer(47))?;
+        key.set("g", Value::Integer(48))?;
+        key.set("h", Value::Integer(49))?;
+        key.set("i", Value::Integer(50))?;
+        key.set("j", Value::Integer(51))?;
+        key.set("k", Value::Integer(52))?;
+        key.set("l", Value::Integer(53))?;
+        key.set("m", Value::Integer(54))?;
+        key.set("n", Value::Integer(55))?;
+        key.set("o", Value::Integer(56))?;
+        key.set("p", Value::Integer(57))?;
+        key.set("q", Value::Integer(58))?;
+        key.set("r", Value::Integer(59))?;
+        key.set("s", Value::Integer(60))?;
+        key.set("t", Value::Integer(61))?;
+        key.set("u", Value::Integer(62))?;
+        key.set("v", Value::Integer(63))?;
+        key.set("w", Value::Integer(64))?;
+        key.set("x", Value::Integer(65))?;
+        key.set("y", Value::Integer(66))?;
+        key.set("z", Value::Integer(67))?;
+        key.set("f1", Value::Integer(68))?;
+        key.set("f2", Value::Integer(69))?;
+        key.set("f3", Value::Integer(70))?;
+        key.set("f4", Value::Integer(71))?;
+        key.set("f5", Value::Integer(72))?;
+        key.set("f6", Value::Integer(73))?;
+        key.set("f7", Value::Integer(74))?;
+        key.set("f8", Value::Integer(75))?;
+        key.set("f9", Value::Integer(76))?;
+        key.set("f10", Value::Integer(77))?;
+        key.set("f11", Value::Integer(78))?;
+        key.set("f12", Value::Integer(79))?;
+        key.set("f13", Value::Integer(80))?;
+        key.set("f14", Value::Integer(81))?;
+        key.set("f15", Value::Integer(82))?;
+        key.set("f16", Value::Integer(83))?;
+        key.set("f17", Value::Integer(84))?;
+        key.set("f18", Value::Integer(85))?;
+        key.set("f19", Value::Integer(86))?;
+        key.set("f20", Value::Integer(87))?;
+        egui_table.set("key", key)?;
+        Ok(())
+    }
+    fn from_lua(value: Value) -> Result<Self> {
+        match value {
+            // the integers are indices into `Key::ALL`, which is what `add_to_lua` uses too.
+            Value::Integer(i) => usize::try_from(i)
+                .ok()
+                .and_then(|i| Key::ALL.get(i).copied())
+                .ok_or_else(|| mlua::Error::FromLuaConversionError {
+                    from: "luavalue",
+                    to: "key",
+                    message: Some("integer value out of range".to_string()),
+                }),
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: "luavalue",
+                to: "key",
+                message: None,
+            }),
+        }
+    }
+
+    fn to_lua<'lua>(value: Self, _lua: &'lua Lua) -> Result<Value<'lua>> {
+        Ok(Value::Integer(
+            Key::ALL
+                .iter()
+                .position(|k| *k == value)
+                .ok_or_else(|| mlua::Error::RuntimeError(format!("unknown key {value:?}")))?
+                as _,
+        ))
+    }
+}
+impl LuaHelperTrait for Modifiers {
+    fn add_to_lua(lua: &Lua, egui_table: &Table) -> Result<()> {
+        let modifiers = lua.create_table()?;
+        modifiers.set("none", Modifiers::to_lua(Modifiers::NONE, lua)?)?;
+        modifiers.set("alt", Modifiers::to_lua(Modifiers::ALT, lua)?)?;
+        modifiers.set("ctrl", Modifiers::to_lua(Modifiers::CTRL, lua)?)?;
+        modifiers.set("shift", Modifiers::to_lua(Modifiers::SHIFT, lua)?)?;
+        modifiers.set("mac_cmd", Modifiers::to_lua(Modifiers::MAC_CMD, lua)?)?;
+        modifiers.set("command", Modifiers::to_lua(Modifiers::COMMAND, lua)?)?;
+        egui_table.set("modifiers", modifiers)?;
+        Ok(())
+    }
+    fn from_lua(value: Value) -> Result<Self> {
+        Ok(match value {
+            Value::Nil => Modifiers::NONE,
+            Value::Table(t) => Modifiers {
+                alt: t.get::<_, Option<bool>>("alt")?.unwrap_or_default(),
+                ctrl: t.get::<_, Option<bool>>("ctrl")?.unwrap_or_default(),
+                shift: t.get::<_, Option<bool>>("shift")?.unwrap_or_default(),
+                mac_cmd: t.get::<_, Option<bool>>("mac_cmd")?.unwrap_or_default(),
+                command: t.get::<_, Option<bool>>("command")?.unwrap_or_default(),
+            },
+            _ => {
+                return Err(mlua::Error::FromLuaConversionError {
+                    from: "luavalue",
+                    to: "modifiers",
+                    message: None,
+                })
+            }
+        })
+    }
+
+    fn to_lua<'lua>(value: Self, lua: &'lua Lua) -> Result<Value<'lua>> {
+        let modifiers = lua.create_table()?;
+        modifiers.set("alt", value.alt)?;
+        modifiers.set("ctrl", value.ctrl)?;
+        modifiers.set("shift", value.shift)?;
+        modifiers.set("mac_cmd", value.mac_cmd)?;
+        modifiers.set("command", value.command)?;
+        Ok(Value::Table(modifiers))
+    }
+}
+impl LuaHelperTrait for WidgetText {
+    fn from_lua(value: Value) -> Result<Self> {
+        match value {
+            Value::String(s) => Ok(s.to_str().unwrap_or_default().into()),
+            Value::UserData(u) => {
+                if let Ok(u) = u.borrow::<WidgetText>() {
+                    Ok(u.clone())
+                } else if let Ok(u) = u.borrow::<RichText>() {
+                    Ok(u.clone().into())
+                } else if let Ok(u) = u.borrow::<LayoutJob>() {
+                    Ok(u.clone().into())
+                } else if let Ok(u) = u.borrow::<Arc<Galley>>() {
+                    Ok(u.clone().into())
+                } else {
+                    return Err(mlua::Error::FromLuaConversionError {
+                        from: "userdata",
+                        to: "widgettext",
+                        message: None,
+                    });
+                }
+            }
+            Value::Table(_) => Ok(RichText::from_lua(value)?.into()),
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: "luavalue",
+                to: "widgettext",
+                message: None,
+            }),
+        }
+    }
+
+    fn to_lua(value: Self, lua: &Lua) -> Result<Value> {
+        Ok(mlua::Value::UserData(lua.create_any_userdata(value)?))
+    }
+
+    fn add_to_lua(lua: &Lua, _egui_table: &Table) -> Result<()> {
+        lua.register_userdata_type(|_reg: &mut UserDataRegistry<WidgetText>| {})?;
+        Ok(())
+    }
+}
+
+impl LuaHelperTrait for RichText {
+    fn from_lua(value: Value) -> Result<Self> {
+        match value {
+            Value::String(s) => Ok(s.to_str().unwrap_or_default().into()),
+            Value::UserData(u) => {
+                if let Ok(u) = u.borrow::<RichText>() {
+                    Ok(u.clone())
+                } else {
+                    Err(mlua::Error::FromLuaConversionError {
+                        from: "userdata",
+                        to: "widgettext",
+                        message: None,
+                    })
+                }
+            }
+            Value::Table(t) => {
+                let mut rich_text = RichText::new(t.get::<_, String>("text")?);
+                if let Some(size) = t.get::<_, Option<f32>>("size")? {
+                    rich_text = rich_text.size(size);
+                }
+                if let Some(line_height) = t.get::<_, Option<f32>>("line_height")? {
+                    rich_text = rich_text.line_height(Some(line_height));
+                }
+                if let Some(spacing) = t.get::<_, Option<f32>>("extra_letter_spacing")? {
+                    rich_text = rich_text.extra_letter_spacing(spacing);
+                }
+                let family: Value = t.get("family")?;
+                if !family.is_nil() {
+                    rich_text = rich_text.family(FontFamily::from_lua(family)?);
+                }
+                let text_style: Value = t.get("text_style")?;
+                if !text_style.is_nil() {
+                    rich_text = rich_text.text_style(TextStyle::from_lua(text_style)?);
+                }
+                let color: Value = t.get("color")?;
+                if !color.is_nil() {
+                    rich_text = rich_text.color(Color32::from_lua(color)?);
+                }
+                let background_color: Value = t.get("background_color")?;
+                if !background_color.is_nil() {
+                    rich_text = rich_text.background_color(Color32::from_lua(background_color)?);
+                }
+                if t.get::<_, Option<bool>>("heading")?.unwrap_or_default() {
+                    rich_text = rich_text.heading();
+                }
+                if t.get::<_, Option<bool>>("monospace")?.unwrap_or_default() {
+                    rich_text = rich_text.monospace();
+                }
+                if t.get::<_, Option<bool>>("code")?.unwrap_or_default() {
+                    rich_text = rich_text.code();
+                }
+                if t.get::<_, Option<bool>>("strong")?.unwrap_or_default() {
+                    rich_text = rich_text.strong();
+                }
+                if t.get::<_, Option<bool>>("weak")?.unwrap_or_default() {
+                    rich_text = rich_text.weak();
+                }
+                if t.get::<_, Option<bool>>("underline")?.unwrap_or_default() {
+                    rich_text = rich_text.underline();
+                }
+                if t.get::<_, Option<bool>>("strikethrough")?
+                    .unwrap_or_default()
+                {
+                    rich_text = rich_text.strikethrough();
+                }
+                if t.get::<_, Option<bool>>("italics")?.unwrap_or_default() {
+                    rich_text = rich_text.italics();
+                }
+                if t.get::<_, Option<bool>>("small")?.unwrap_or_default() {
+                    rich_text = rich_text.small();
+                }
+                if t.get::<_, Option<bool>>("raised")?.unwrap_or_default() {
+                    rich_text = rich_text.raised();
+                }
+                Ok(rich_text)
+            }
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: "luavalue",
+                to: "widgettext",
+                message: None,
+            }),
+        }
+    }
+
+    fn to_lua(value: Self, lua: &Lua) -> Result<Value> {
+        Ok(Value::UserData(lua.create_any_userdata(value)?))
+    }
+
+    fn add_to_lua(lua: &Lua, egui_table: &Table) -> Result<()> {
+        lua.register_userdata_type(|reg: &mut UserDataRegistry<RichText>| {
+            reg.add_method("is_empty", |_, this, ()| Ok(this.is_empty()));
+            reg.add_method("text", |_, this, ()| Ok(this.text().to_string()));
+            reg.add_method("size", |lua, this, size: f32| {
+                lua.create_any_userdata(this.clone().size(size))
+            });
+            reg.add_method("extra_letter_spacing", |lua, this, spacing: f32| {
+                lua.create_any_userdata(this.clone().extra_letter_spacing(spacing))
+            });
+            reg.add_method("line_height", |lua, this, line_height: Option<f32>| {
+                lua.create_any_userdata(this.clone().line_height(line_height))
+            });
+            reg.add_method("family", |lua, this, family: Value| {
+                let family = FontFamily::from_lua(family)?;
+                with_known_fonts(lua, |known| check_font_family(&known.families, &family))?;
+                lua.create_any_userdata(this.clone().family(family))
+            });
+            reg.add_method("text_style", |lua, this, text_style: Value| {
+                let text_style = TextStyle::from_lua(text_style)?;
+                with_known_fonts(lua, |known| {
+                    check_text_style(&known.text_styles, &text_style)
+                })?;
+                lua.create_any_userdata(this.clone().text_style(text_style))
+            });
+            reg.add_method("heading", |lua, this, ()| {
+                lua.create_any_userdata(this.clone().heading())
+            });
+            reg.add_method("monospace", |lua, this, ()| {
+                lua.create_any_userdata(this.clone().monospace())
+            });
+            reg.add_method("code", |lua, this, ()| {
+                lua.create_any_userdata(this.clone().code())
+            });
+            reg.add_method("strong", |lua, this, ()| {
+                lua.create_any_userdata(this.clone().strong())
+            });
+            reg.add_method("weak", |lua, this, ()| {
+                lua.create_any_userdata(this.clone().weak())
+            });
+            reg.add_method("underline", |lua, this, ()| {
+                lua.create_any_userdata(this.clone().underline())
+            });
+            reg.add_method("strikethrough", |lua, this, ()| {
+                lua.create_any_userdata(this.clone().strikethrough())
+            });
+            reg.add_method("italics", |lua, this, ()| {
+                lua.create_any_userdata(this.clone().italics())
+            });
+            reg.add_method("small", |lua, this, ()| {
+                lua.create_any_userdata(this.clone().small())
+            });
+            reg.add_method("small_raised", |lua, this, ()| {
+                lua.create_any_userdata(this.clone().small_raised())
+            });
+            reg.add_method("raised", |lua, this, ()| {
+                lua.create_any_userdata(this.clone().raised())
+            });
+            reg.add_method("color", |lua, this, color: Value| {
+                lua.create_any_userdata(this.clone().color(Color32::from_lua(color)?))
+            });
+            reg.add_method("background_color", |lua, this, color: Value| {
+                lua.create_any_userdata(this.clone().background_color(Color32::from_lua(color)?))
+            });
+        })?;
+        let rich_text = lua.create_table()?;
+        rich_text.set(
+            "new",
+            lua.create_function(|lua, text: String| lua.create_any_userdata(RichText::new(text)))?,
+        )?;
+
+        egui_table.set("rich_text", rich_text)
+    }
+}
+
+#[derive(Hash, Debug)]
+enum LuaHashable<'lua> {
+    LuaString(mlua::String<'lua>),
+    Integer(i32),
+}
+impl<'lua> LuaHashable<'lua> {
+    fn from_lua(value: Value<'lua>) -> Result<Self> {
+        match value {
+            Value::Integer(i) => Ok(Self::Integer(i)),
+            Value::String(i) => Ok(Self::LuaString(i)),
+            _ => Err(mlua::Error::FromLuaConversionError {
+                from: "value",
+                to: "LuaHashable",
+                message: None,
+            }),
+        }
+    }
+}
+/// `egui::Slider` borrows the number it edits, so we keep the options around
+/// and build the slider when it is added to a `Ui`.
+/// The lua table holding the `value` field is stored as the userdata's user value.
+#[derive(Default)]
+struct SliderBuilder {
+    min: f64,
+    max: f64,
+    step_by: Option<f64>,
+    clamp_to_range: Option<bool>,
+    logarithmic: bool,
+    prefix: String,
+    suffix: String,
+    fixed_decimals: Option<usize>,
+    text: Option<WidgetText>,
+    orientation: Option<SliderOrientation>,
+}
+impl SliderBuilder {
+    fn build<'a>(&self, value: &'a mut f64) -> Slider<'a> {
+        let mut slider = Slider::new(value, self.min..=self.max)
+            .logarithmic(self.logarithmic)
+            .prefix(&self.prefix)
+            .suffix(&self.suffix);
+        if let Some(step) = self.step_by {
+            slider = slider.step_by(step);
+        }
+        if let Some(clamp_to_range) = self.clamp_to_range {
+            slider = slider.clamp_to_range(clamp_to_range);
+        }
+        if let Some(num_decimals) = self.fixed_decimals {
+            slider = slider.fixed_decimals(num_decimals);
+        }
+        if let Some(text) = self.text.clone() {
+            slider = slider.text(text);
+        }
+        if let Some(SliderOrientation::Vertical) = self.orientation {
+            slider = slider.vertical();
+        }
+        slider
+    }
+}
+/// Same as [`SliderBuilder`], but for `egui::DragValue`.
+#[derive(Default)]
+struct DragValueBuilder {
+    speed: Option<f64>,
+    clamp_range: Option<(f64, f64)>,
+    prefix: String,
+    suffix: String,
+    fixed_decimals: Option<usize>,
+}
+impl DragValueBuilder {
+    fn build<'a>(&self, value: &'a mut f64) -> DragValue<'a> {
+        let mut drag_value = DragValue::new(value)
+            .prefix(&self.prefix)
+            .suffix(&self.suffix);
+        if let Some(speed) = self.speed {
+            drag_value = drag_value.speed(speed);
+        }
+        if let Some((min, max)) = self.clamp_range {
+            drag_value = drag_value.clamp_range(min..=max);
+        }
+        if let Some(num_decimals) = self.fixed_decimals {
+            drag_value = drag_value.fixed_decimals(num_decimals);
+        }
+        drag_value
+    }
+}
+/// Applies `f` to the widget stored in `this` and returns `this`, so that lua can chain builder calls.
+/// Widgets are stored as `Option<W>` because adding them to a `Ui` consumes them.
+fn chain_widget<'lua, W: 'static>(
+    this: AnyUserData<'lua>,
+    f: impl FnOnce(W) -> W,
+) -> Result<AnyUserData<'lua>> {
+    {
+        let mut widget = this.borrow_mut::<Option<W>>()?;
+        let w = widget
+            .take()
+            .ok_or_else(|| mlua::Error::RuntimeError("widget is null".to_owned()))?;
+        *widget = Some(f(w));
+    }
+    Ok(this)
+}
+/// Takes the widget out of `widget` if it holds an `Option<W>`.
+fn take_widget<W: 'static>(widget: &AnyUserData) -> Result<Option<W>> {
+    match widget.borrow_mut::<Option<W>>() {
+        Ok(mut w) => w.take().map(Some).ok_or_else(|| {
+            mlua::Error::RuntimeError("widget was already added to a ui".to_owned())
+        }),
+        Err(_) => Ok(None),
+    }
+}
+/// `egui::Checkbox` borrows the bool it edits, so we build it when it is added to a `Ui`.
+/// The lua table holding the `checked` field is stored as the userdata's user value.
+struct CheckboxBuilder {
+    text: Option<WidgetText>,
+    indeterminate: bool,
+}
+/// `egui::TextEdit` borrows the string it edits, so we build it when it is added to a `Ui`.
+/// The lua table holding the `text` field is stored as the userdata's user value,
+/// and the optional layouter fn as the second user value.
+#[derive(Default)]
+struct TextEditBuilder {
+    multiline: bool,
+    code_editor: bool,
+    id: Option<Id>,
+    id_source: Option<Id>,
+    hint_text: Option<WidgetText>,
+    password: Option<bool>,
+    font: Option<FontSelection>,
+    text_color: Option<Color32>,
+    interactive: Option<bool>,
+    frame: Option<bool>,
+    margin: Option<Vec2>,
+    desired_width: Option<f32>,
+    desired_rows: Option<usize>,
+    lock_focus: Option<bool>,
+    cursor_at_end: Option<bool>,
+    clip_text: Option<bool>,
+    char_limit: Option<usize>,
+    horizontal_align: Option<Align>,
+    vertical_align: Option<Align>,
+    min_size: Option<Vec2>,
+}
+impl TextEditBuilder {
+    fn build<'t>(&self, text: &'t mut String) -> TextEdit<'t> {
+        let mut text_edit = if self.multiline {
+            TextEdit::multiline(text)
+        } else {
+            TextEdit::singleline(text)
+        };
+        if self.code_editor {
+            text_edit = text_edit.code_editor();
+        }
+        if let Some(id) = self.id {
+            text_edit = text_edit.id(id);
+        }
+        if let Some(id_source) = self.id_source {
+            text_edit = text_edit.id_source(id_source);
+        }
+        if let Some(hint_text) = self.hint_text.clone() {
+            text_edit = text_edit.hint_text(hint_text);
+        }
+        if let Some(password) = self.password {
+            text_edit = text_edit.password(password);
+        }
+        // `FontSelection` isn't `Clone`
+        match &self.font {
+            Some(FontSelection::FontId(font_id)) => text_edit = text_edit.font(font_id.clone()),
+            Some(FontSelection::Style(text_style)) => {
+                text_edit = text_edit.font(text_style.clone())
+            }
+            Some(FontSelection::Default) | None => {}
+        }
+        text_edit = text_edit.text_color_opt(self.text_color);
+        if let Some(interactive) = self.interactive {
+            text_edit = text_edit.interactive(interactive);
+        }
+        if let Some(frame) = self.frame {
+            text_edit = text_edit.frame(frame);
+        }
+        if let Some(margin) = self.margin {
+            text_edit = text_edit.margin(margin);
+        }
+        if let Some(desired_width) = self.desired_width {
+            text_edit = text_edit.desired_width(desired_width);
+        }
+        if let Some(desired_rows) = self.desired_rows {
+            text_edit = text_edit.desired_rows(desired_rows);
+        }
+        if let Some(lock_focus) = self.lock_focus {
+            text_edit = text_edit.lock_focus(lock_focus);
+        }
+        if let Some(cursor_at_end) = self.cursor_at_end {
+            text_edit = text_edit.cursor_at_end(cursor_at_end);
+        }
+        if let Some(clip_text) = self.clip_text {
+            text_edit = text_edit.clip_text(clip_text);
+        }
+        if let Some(char_limit) = self.char_limit {
+            text_edit = text_edit.char_limit(char_limit);
+        }
+        if let Some(align) = self.horizontal_align {
+            text_edit = text_edit.horizontal_align(align);
+        }
+        if let Some(align) = self.vertical_align {
+            text_edit = text_edit.vertical_align(align);
+        }
+        if let Some(min_size) = self.min_size {
+            text_edit = text_edit.min_size(min_size);
+        }
+        text_edit
+    }
+}
+/// Builds the `TextEdit` of a [`TextEditBuilder`] userdata, gives it to `show` and writes the text back to lua.
+/// The lua layouter fn gets `(ui, text, wrap_width)` and returns anything `WidgetText::from_lua` accepts.
+fn show_text_edit<R>(
+    lua: &Lua,
+    text_edit: &AnyUserData,
+    show: impl FnOnce(TextEdit) -> R,
+) -> Result<R> {
+    let table: Table = text_edit.user_value()?;
+    let layouter_fn: Option<Function> = text_edit.nth_user_value(2)?;
+    let builder = text_edit.take::<TextEditBuilder>()?;
+    let mut text: String = table.get("text")?;
+    // the layouter can't return errors to egui, so we keep the first one and fall back to plain text.
+    let layouter_error = Cell::new(None);
+    let mut layouter = layouter_fn.map(|layouter_fn| {
+        let layouter_error = &layouter_error;
+        move |ui: &Ui, text: &str, wrap_width: f32| {
+            let widget_text = lua
+                .scope(|scope| {
+                    let ui = scope.create_any_userdata_ref(ui)?;
+                    layouter_fn.call::<_, Value>((ui, text, wrap_width))
+                })
+                .and_then(|value| widget_text_from_lua(lua, value));
+            let widget_text = match widget_text {
+                Ok(widget_text) => widget_text,
+                Err(e) => {
+                    let first_error = layouter_error.take().unwrap_or(e);
+                    layouter_error.set(Some(first_error));
+                    WidgetText::from(text)
+                }
+            };
+            widget_text.into_galley(ui, None, wrap_width, FontSelection::Default)
+        }
+    });
+    let mut widget = builder.build(&mut text);
+    if let Some(layouter) = &mut layouter {
+        widget = widget.layouter(layouter);
+    }
+    let result = show(widget);
+    table.set("text", text)?;
+    match layouter_error.into_inner() {
+        Some(e) => Err(e),
+        None => Ok(result),
+    }
+}
+fn text_edit_output_to_lua<'lua>(lua: &'lua Lua, output: TextEditOutput) -> Result<Table<'lua>> {
+    let table = lua.create_table()?;
+    table.set("response", lua.create_any_userdata(output.response)?)?;
+    table.set("galley", lua.create_any_userdata(output.galley)?)?;
+    table.set("galley_pos", Pos2::to_lua(output.galley_pos, lua)?)?;
+    table.set("text_clip_rect", Rect::to_lua(output.text_clip_rect, lua)?)?;
+    // cursors are char indices into the text
+    if let Some(cursor_range) = output.cursor_range {
+        let range = lua.create_table()?;
+        range.set("primary", cursor_range.primary.ccursor.index)?;
+        range.set("secondary", cursor_range.secondary.ccursor.index)?;
+        table.set("cursor_range", range)?;
+    }
+    Ok(table)
+}
+/// Builds the egui widget out of a widget builder userdata and gives it to `place`
+/// (which is usually one of the `Ui::add*` fns). Any edited values are written back to lua.
+/// Like `Window`, every widget builder is consumed when it is added, so scripts create them each frame.
+fn add_lua_widget(
+    lua: &Lua,
+    widget: AnyUserData,
+    place: impl FnOnce(Box<dyn FnOnce(&mut Ui) -> egui::Response + '_>) -> egui::Response,
+) -> Result<egui::Response> {
+    // the builders that are taken out below are left destructed
+    if let Err(mlua::Error::UserDataDestructed) = widget.borrow::<()>() {
+        return Err(mlua::Error::RuntimeError(
+            "widget was already added to a ui".to_owned(),
+        ));
+    }
+    if widget.is::<SliderBuilder>() {
+        let table: Table = widget.user_value()?;
+        let slider = widget.take::<SliderBuilder>()?;
+        let mut value: f64 = table.get("value")?;
+        let response = place(Box::new(|ui| ui.add(slider.build(&mut value))));
+        table.set("value", value)?;
+        return Ok(response);
+    }
+    if widget.is::<DragValueBuilder>() {
+        let table: Table = widget.user_value()?;
+        let drag_value = widget.take::<DragValueBuilder>()?;
+        let mut value: f64 = table.get("value")?;
+        let response = place(Box::new(|ui| ui.add(drag_value.build(&mut value))));
+        table.set("value", value)?;
+        return Ok(response);
+    }
+    if widget.is::<CheckboxBuilder>() {
+        let table: Table = widget.user_value()?;
+        let checkbox = widget.take::<CheckboxBuilder>()?;
+        let mut checked: bool = table.get("checked")?;
+        let response = place(Box::new(|ui| {
+            let widget = match checkbox.text.clone() {
+                Some(text) => Checkbox::new(&mut checked, text),
+                None => Checkbox::without_text(&mut checked),
+            };
+            ui.add(widget.indeterminate(checkbox.indeterminate))
+        }));
+        table.set("checked", checked)?;
+        return Ok(response);
+    }
+    if widget.is::<TextEditBuilder>() {
+        return show_text_edit(lua, &widget, |text_edit| {
+            place(Box::new(|ui| ui.add(text_edit)))
+        });
+    }
+    if let Some(w) = take_widget::<Button<'static>>(&widget)? {
+        return Ok(place(Box::new(|ui| ui.add(w))));
+    }
+    if let Some(w) = take_widget::<Label>(&widget)? {
+        return Ok(place(Box::new(|ui| ui.add(w))));
+    }
+    if let Some(w) = take_widget::<RadioButton>(&widget)? {
+        return Ok(place(Box::new(|ui| ui.add(w))));
+    }
+    if let Some(w) = take_widget::<SelectableLabel>(&widget)? {
+        return Ok(place(Box::new(|ui| ui.add(w))));
+    }
+    if let Some(w) = take_widget::<Hyperlink>(&widget)? {
+        return Ok(place(Box::new(|ui| ui.add(w))));
+    }
+    if let Some(w) = take_widget::<Link>(&widget)? {
+        return Ok(place(Box::new(|ui| ui.add(w))));
+    }
+    if let Some(w) = take_widget::<Separator>(&widget)? {
+        return Ok(place(Box::new(|ui| ui.add(w))));
+    }
+    if let Some(w) = take_widget::<Spinner>(&widget)? {
+        return Ok(place(Box::new(|ui| ui.add(w))));
+    }
+    if let Some(w) = take_widget::<ProgressBar>(&widget)? {
+        return Ok(place(Box::new(|ui| ui.add(w))));
+    }
+    if let Some(w) = take_widget::<Image<'static>>(&widget)? {
+        return Ok(place(Box::new(|ui| ui.add(w))));
+    }
+    if let Some(w) = take_widget::<ImageButton<'static>>(&widget)? {
+        return Ok(place(Box::new(|ui| ui.add(w))));
+    }
+    Err(mlua::Error::RuntimeError(
+        "userdata is not a widget that can be added to ui".to_owned(),
+    ))
+}
+fn add_slider(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|slider: &mut UserDataRegistry<SliderBuilder>| {
+        slider.add_function("step_by", |_, (this, step): (AnyUserData, f64)| {
+            this.borrow_mut::<SliderBuilder>()?.step_by = Some(step);
+            Ok(this)
+        });
+        slider.add_function(
+            "clamp_to_range",
+            |_, (this, clamp_to_range): (AnyUserData, bool)| {
+                this.borrow_mut::<SliderBuilder>()?.clamp_to_range = Some(clamp_to_range);
+                Ok(this)
+            },
+        );
+        slider.add_function(
+            "logarithmic",
+            |_, (this, logarithmic): (AnyUserData, bool)| {
+                this.borrow_mut::<SliderBuilder>()?.logarithmic = logarithmic;
+                Ok(this)
+            },
+        );
+        slider.add_function("prefix", |_, (this, prefix): (AnyUserData, String)| {
+            this.borrow_mut::<SliderBuilder>()?.prefix = prefix;
+            Ok(this)
+        });
+        slider.add_function("suffix", |_, (this, suffix): (AnyUserData, String)| {
+            this.borrow_mut::<SliderBuilder>()?.suffix = suffix;
+            Ok(this)
+        });
+        slider.add_function(
+            "fixed_decimals",
+            |_, (this, num_decimals): (AnyUserData, usize)| {
+                this.borrow_mut::<SliderBuilder>()?.fixed_decimals = Some(num_decimals);
+                Ok(this)
+            },
+        );
+        slider.add_function("text", |lua, (this, text): (AnyUserData, Value)| {
+            this.borrow_mut::<SliderBuilder>()?.text = Some(widget_text_from_lua(lua, text)?);
+            Ok(this)
+        });
+        slider.add_function(
+            "orientation",
+            |_, (this, orientation): (AnyUserData, Value)| {
+                this.borrow_mut::<SliderBuilder>()?.orientation =
+                    Some(SliderOrientation::from_lua(orientation)?);
+                Ok(this)
+            },
+        );
+    })?;
+    let slider = lua.create_table()?;
+    slider.set(
+        "new",
+        lua.create_function(|lua, (value, min, max): (Table, f64, f64)| {
+            let slider = lua.create_any_userdata(SliderBuilder {
+                min,
+                max,
+                ..Default::default()
+            })?;
+            slider.set_user_value(value)?;
+            Ok(slider)
+        })?,
+    )?;
+    egui_table.set("slider", slider)?;
+    Ok(())
+}
+fn collapsing_response_to_lua<'lua>(
+    lua: &'lua Lua,
+    response: CollapsingResponse<Result<MultiValue<'lua>>>,
+) -> Result<MultiValue<'lua>> {
+    let table = lua.create_table()?;
+    table.set("fully_closed", response.fully_closed())?;
+    table.set("fully_open", response.fully_open())?;
+    table.set(
+        "header_response",
+        lua.create_any_userdata(response.header_response)?,
+    )?;
+    if let Some(body_response) = response.body_response {
+        table.set("body_response", lua.create_any_userdata(body_response)?)?;
+    }
+    table.set("openness", response.openness)?;
+    let mut i = response.body_returned.transpose()?.unwrap_or_default();
+    i.push_front(Value::Table(table));
+    Ok(i)
+}
+fn add_collapsing_header(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(
+        |collapsing_header: &mut UserDataRegistry<Option<CollapsingHeader>>| {
+            collapsing_header.add_function(
+                "default_open",
+                |_, (this, open): (AnyUserData, bool)| {
+                    chain_widget(this, |c: CollapsingHeader| c.default_open(open))
+                },
+            );
+            collapsing_header.add_function(
+                "open",
+                |_, (this, open): (AnyUserData, Option<bool>)| {
+                    chain_widget(this, |c: CollapsingHeader| c.open(open))
+                },
+            );
+            collapsing_header.add_function(
+                "id_source",
+                |_, (this, id_source): (AnyUserData, Value)| {
+                    let id_source = LuaHashable::from_lua(id_source)?;
+                    chain_widget(this, |c: CollapsingHeader| c.id_source(id_source))
+                },
+            );
+            collapsing_header.add_function("enabled", |_, (this, enabled): (AnyUserData, bool)| {
+                chain_widget(this, |c: CollapsingHeader| c.enabled(enabled))
+            });
+            collapsing_header.add_function(
+                "show_background",
+                |_, (this, show_background): (AnyUserData, bool)| {
+                    chain_widget(this, |c: CollapsingHeader| {
+                        c.show_background(show_background)
+                    })
+                },
+            );
+            // the icon fn is kept as the user value and called in `show`
+            collapsing_header.add_function(
+                "icon",
+                |_, (this, icon_fn): (AnyUserData, Function)| {
+                    this.set_user_value(icon_fn)?;
+                    Ok(this)
+                },
+            );
+            collapsing_header.add_function(
+                "show",
+                |lua, (this, ui, add_contents): (AnyUserData, AnyUserData, Function)| {
+                    let icon_fn: Option<Function> = this.user_value()?;
+                    let mut collapsing_header = this
+                        .borrow_mut::<Option<CollapsingHeader>>()?
+                        .take()
+                        .ok_or_else(|| {
+                            mlua::Error::RuntimeError("collapsing header is null".to_owned())
+                        })?;
+                    // egui wants a 'static icon painter, so we record its arguments and
+                    // call the lua fn with the same ui once the header has been shown.
+                    let icon_args = Rc::new(Cell::new(None));
+                    if icon_fn.is_some() {
+                        let icon_args = icon_args.clone();
+                        collapsing_header = collapsing_header.icon(move |_, openness, response| {
+                            icon_args.set(Some((openness, response.clone())));
+                        });
+                    }
+                    let response = collapsing_header.show(&mut *ui.borrow_mut::<Ui>()?, |ui| {
+                        lua.scope(|scope| {
+                            let ui = scope.create_any_userdata_ref_mut(ui)?;
+                            let result: Result<MultiValue> = add_contents.call(ui);
+                            result
+                        })
+                    });
+                    if let (Some(icon_fn), Some((openness, response))) = (icon_fn, icon_args.take())
+                    {
+                        let _: () =
+                            icon_fn.call((ui, openness, lua.create_any_userdata(response)?))?;
+                    }
+                    collapsing_response_to_lua(lua, response)
+                },
+            );
+        },
+    )?;
+    let collapsing_header = lua.create_table()?;
+    collapsing_header.set(
+        "new",
+        lua.create_function(|lua, text: Value| {
+            lua.create_any_userdata(Some(CollapsingHeader::new(widget_text_from_lua(
+                lua, text,
+            )?)))
+        })?,
+    )?;
+    egui_table.set("collapsing_header", collapsing_header)?;
+    Ok(())
+}
+fn add_collapsing_state(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|collapsing_state: &mut UserDataRegistry<CollapsingState>| {
+        collapsing_state.add_method("store", |_, this, ctx: UserDataRef<Context>| {
+            this.store(&ctx);
+            Ok(())
+        });
+        collapsing_state.add_method("remove", |_, this, ctx: UserDataRef<Context>| {
+            this.remove(&ctx);
+            Ok(())
+        });
+        collapsing_state.add_method("id", |lua, this, ()| lua.create_any_userdata(this.id()));
+        collapsing_state.add_method("is_open", |_, this, ()| Ok(this.is_open()));
+        collapsing_state.add_method_mut("set_open", |_, this, open: bool| {
+            this.set_open(open);
+            Ok(())
+        });
+        collapsing_state.add_method_mut("toggle", |_, this, ui: UserDataRef<Ui>| {
+            this.toggle(&ui);
+            Ok(())
+        });
+        collapsing_state.add_method("openness", |_, this, ctx: UserDataRef<Context>| {
+            Ok(this.openness(&ctx))
+        });
+        collapsing_state.add_method_mut(
+            "show_toggle_button",
+            |lua, this, mut ui: UserDataRefMut<Ui>| {
+                lua.create_any_userdata(
+                    this.show_toggle_button(&mut ui, egui::collapsing_header::paint_default_icon),
+                )
+            },
+        );
+        collapsing_state.add_method_mut(
+            "show_body_indented",
+            |lua,
+             this,
+             (header_response, mut ui, add_body): (
+                UserDataRef<egui::Response>,
+                UserDataRefMut<Ui>,
+                Function,
+            )| {
+                let Some(ir) = this.show_body_indented(&header_response, &mut ui, |ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_body.call(ui);
+                        result
+                    })
+                }) else {
+                    return Ok(MultiValue::new());
+                };
+                let r = lua.create_any_userdata(ir.response)?;
+                let mut i = ir.inner?;
+                i.push_front(Value::UserData(r));
+                Ok(i)
+            },
+        );
+        collapsing_state.add_method_mut(
+            "show_body_unindented",
+            |lua, this, (mut ui, add_body): (UserDataRefMut<Ui>, Function)| {
+                let Some(ir) = this.show_body_unindented(&mut ui, |ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_body.call(ui);
+                        result
+                    })
+                }) else {
+                    return Ok(MultiValue::new());
+                };
+                let r = lua.create_any_userdata(ir.response)?;
+                let mut i = ir.inner?;
+                i.push_front(Value::UserData(r));
+                Ok(i)
+            },
+        );
+    })?;
+    let collapsing_state = lua.create_table()?;
+    collapsing_state.set(
+        "load",
+        lua.create_function(|lua, (ctx, id): (UserDataRef<Context>, Value)| {
+            CollapsingState::load(&ctx, Id::from_lua(id)?)
+                .map(|state| lua.create_any_userdata(state))
+                .transpose()
+        })?,
+    )?;
+    collapsing_state.set(
+        "load_with_default_open",
+        lua.create_function(
+            |lua, (ctx, id, default_open): (UserDataRef<Context>, Value, bool)| {
+                lua.create_any_userdata(CollapsingState::load_with_default_open(
+                    &ctx,
+                    Id::from_lua(id)?,
+                    default_open,
+                ))
+            },
+        )?,
+    )?;
+    egui_table.set("collapsing_state", collapsing_state)?;
+    Ok(())
+}
+/// Image bytes can be a lua string or a table of integers.
+fn bytes_from_lua(value: Value) -> Result<Vec<u8>> {
+    match value {
+        Value::String(s) => Ok(s.as_bytes().to_vec()),
+        Value::Table(t) => t.sequence_values::<u8>().collect(),
+        _ => Err(mlua::Error::FromLuaConversionError {
+            from: "luavalue",
+            to: "bytes",
+            message: Some("expected a string or a table of bytes".to_string()),
+        }),
+    }
+}
+/// Same limit as the largest texture side that most gpus support.
+const MAX_IMAGE_SIDE: usize = 16384;
+/// egui asserts on the length of the pixel data, so we check it first instead of panicking.
+fn check_image_bytes(
+    width: usize,
+    height: usize,
+    bytes_per_pixel: usize,
+    bytes: &[u8],
+) -> Result<()> {
+    if width > MAX_IMAGE_SIDE || height > MAX_IMAGE_SIDE {
+        return Err(mlua::Error::RuntimeError(format!(
+            "image size {width}x{height} must be at most {MAX_IMAGE_SIDE} on each side"
+        )));
+    }
+    let expected = width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(bytes_per_pixel))
+        .ok_or_else(|| {
+            mlua::Error::RuntimeError(format!("a {width}x{height} image is too large"))
+        })?;
+    if expected != bytes.len() {
+        return Err(mlua::Error::RuntimeError(format!(
+            "expected {expected} bytes for a {width}x{height} image, got {}",
+            bytes.len()
+        )));
+    }
+    Ok(())
+}
+/// `as usize` would silently turn negative or NaN sizes into 0, and huge ones abort on allocation.
+fn image_size_from_lua(size: Value) -> Result<[usize; 2]> {
+    let size = Vec2::from_lua(size)?;
+    let max_side = MAX_IMAGE_SIDE as f32;
+    if !(0.0..=max_side).contains(&size.x) || !(0.0..=max_side).contains(&size.y) {
+        return Err(mlua::Error::RuntimeError(format!(
+            "image size {size:?} must be between 0 and {MAX_IMAGE_SIDE} on each side"
+        )));
+    }
+    Ok([size.x as usize, size.y as usize])
+}
+/// Pixels are addressed with zero based `x, y` coordinates.
+fn pixel_index(image: &ColorImage, x: usize, y: usize) -> Result<usize> {
+    let [width, height] = image.size;
+    if x >= width || y >= height {
+        return Err(mlua::Error::RuntimeError(format!(
+            "pixel ({x}, {y}) is out of bounds for a {width}x{height} image"
+        )));
+    }
+    Ok(y * width + x)
+}
+fn add_color_image(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|color_image: &mut UserDataRegistry<ColorImage>| {
+        color_image.add_method("size", |lua, this, ()| {
+            Vec2::to_lua(Vec2::new(this.size[0] as f32, this.size[1] as f32), lua)
+        });
+        color_image.add_method("width", |_, this, ()| Ok(this.width()));
+        color_image.add_method("height", |_, this, ()| Ok(this.height()));
+        color_image.add_method("get_pixel", |lua, this, (x, y): (usize, usize)| {
+            Color32::to_lua(this.pixels[pixel_index(this, x, y)?], lua)
+        });
+        color_image.add_method_mut(
+            "set_pixel",
+            |_, this, (x, y, color): (usize, usize, Value)| {
+                let index = pixel_index(this, x, y)?;
+                this.pixels[index] = Color32::from_lua(color)?;
+                Ok(())
+            },
+        );
+        color_image.add_method_mut("fill", |_, this, color: Value| {
+            let color = Color32::from_lua(color)?;
+            this.pixels.fill(color);
+            Ok(())
+        });
+    })?;
+    let color_image = lua.create_table()?;
+    color_image.set(
+        "new",
+        lua.create_function(|lua, (size, color): (Value, Value)| {
+            lua.create_any_userdata(ColorImage::new(
+                image_size_from_lua(size)?,
+                Color32::from_lua(color)?,
+            ))
+        })?,
+    )?;
+    color_image.set(
+        "from_rgba_unmultiplied",
+        lua.create_function(|lua, (width, height, bytes): (usize, usize, Value)| {
+            let bytes = bytes_from_lua(bytes)?;
+            check_image_bytes(width, height, 4, &bytes)?;
+            lua.create_any_userdata(ColorImage::from_rgba_unmultiplied([width, height], &bytes))
+        })?,
+    )?;
+    color_image.set(
+        "from_rgba_premultiplied",
+        lua.create_function(|lua, (width, height, bytes): (usize, usize, Value)| {
+            let bytes = bytes_from_lua(bytes)?;
+            check_image_bytes(width, height, 4, &bytes)?;
+            lua.create_any_userdata(ColorImage::from_rgba_premultiplied([width, height], &bytes))
+        })?,
+    )?;
+    color_image.set(
+        "from_rgb",
+        lua.create_function(|lua, (width, height, bytes): (usize, usize, Value)| {
+            let bytes = bytes_from_lua(bytes)?;
+            check_image_bytes(width, height, 3, &bytes)?;
+            lua.create_any_userdata(ColorImage::from_rgb([width, height], &bytes))
+        })?,
+    )?;
+    color_image.set(
+        "from_gray",
+        lua.create_function(|lua, (width, height, bytes): (usize, usize, Value)| {
+            let bytes = bytes_from_lua(bytes)?;
+            check_image_bytes(width, height, 1, &bytes)?;
+            lua.create_any_userdata(ColorImage::from_gray([width, height], &bytes))
+        })?,
+    )?;
+    egui_table.set("color_image", color_image)?;
+    Ok(())
+}
+fn add_combo_box(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|combo_box: &mut UserDataRegistry<Option<ComboBox>>| {
+        combo_box.add_function("width", |_, (this, width): (AnyUserData, f32)| {
+            chain_widget(this, |c: ComboBox| c.width(width))
+        });
+        combo_box.add_function("height", |_, (this, height): (AnyUserData, f32)| {
+            chain_widget(this, |c: ComboBox| c.height(height))
+        });
+        combo_box.add_function(
+            "selected_text",
+            |lua, (this, selected_text): (AnyUserData, Value)| {
+                let selected_text = widget_text_from_lua(lua, selected_text)?;
+                chain_widget(this, |c: ComboBox| c.selected_text(selected_text))
+            },
+        );
+        combo_box.add_function("wrap", |_, (this, wrap): (AnyUserData, bool)| {
+            chain_widget(this, |c: ComboBox| c.wrap(wrap))
+        });
+        // the icon fn is kept as the user value and called in `show_ui`
+        combo_box.add_function("icon", |_, (this, icon_fn): (AnyUserData, Function)| {
+            this.set_user_value(icon_fn)?;
+            Ok(this)
+        });
+        combo_box.add_function(
+            "show_ui",
+            |lua, (this, ui, menu_contents): (AnyUserData, AnyUserData, Function)| {
+                let icon_fn: Option<Function> = this.user_value()?;
+                let mut combo_box = this
+                    .borrow_mut::<Option<ComboBox>>()?
+                    .take()
+                    .ok_or_else(|| mlua::Error::RuntimeError("combo box is null".to_owned()))?;
+                // egui wants a 'static icon painter, so we record its arguments and
+                // call the lua fn with the same ui once the combo box has been shown.
+                let icon_args = Rc::new(Cell::new(None));
+                if icon_fn.is_some() {
+                    let icon_args = icon_args.clone();
+                    combo_box = combo_box.icon(move |_, rect, visuals, is_open, above_or_below| {
+                        icon_args.set(Some((rect, *visuals, is_open, above_or_below)));
+                    });
+                }
+                let ir = combo_box.show_ui(&mut *ui.borrow_mut::<Ui>()?, |ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = menu_contents.call(ui);
+                        result
+                    })
+                });
+                if let (Some(icon_fn), Some((rect, visuals, is_open, above_or_below))) =
+                    (icon_fn, icon_args.take())
+                {
+                    let _: () = icon_fn.call((
+                        ui,
+                        Rect::to_lua(rect, lua)?,
+                        lua.create_any_userdata(visuals)?,
+                        is_open,
+                        AboveOrBelow::to_lua(above_or_below, lua)?,
+                    ))?;
+                }
+                let r = lua.create_any_userdata(ir.response)?;
+                let mut i = ir.inner.transpose()?.unwrap_or_default();
+                i.push_front(Value::UserData(r));
+                Ok(i)
+            },
+        );
+    })?;
+    let combo_box = lua.create_table()?;
+    combo_box.set(
+        "from_label",
+        lua.create_function(|lua, label: Value| {
+            lua.create_any_userdata(Some(ComboBox::from_label(widget_text_from_lua(
+                lua, label,
+            )?)))
+        })?,
+    )?;
+    combo_box.set(
+        "from_id_source",
+        lua.create_function(|lua, id_source: Value| {
+            lua.create_any_userdata(Some(ComboBox::from_id_source(LuaHashable::from_lua(
+                id_source,
+            )?)))
+        })?,
+    )?;
+    egui_table.set("combo_box", combo_box)?;
+    Ok(())
+}
+fn add_drag_value(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|drag_value: &mut UserDataRegistry<DragValueBuilder>| {
+        drag_value.add_function("speed", |_, (this, speed): (AnyUserData, f64)| {
+            this.borrow_mut::<DragValueBuilder>()?.speed = Some(speed);
+            Ok(this)
+        });
+        drag_value.add_function(
+            "clamp_range",
+            |_, (this, min, max): (AnyUserData, f64, f64)| {
+                this.borrow_mut::<DragValueBuilder>()?.clamp_range = Some((min, max));
+                Ok(this)
+            },
+        );
+        drag_value.add_function("prefix", |_, (this, prefix): (AnyUserData, String)| {
+            this.borrow_mut::<DragValueBuilder>()?.prefix = prefix;
+            Ok(this)
+        });
+        drag_value.add_function("suffix", |_, (this, suffix): (AnyUserData, String)| {
+            this.borrow_mut::<DragValueBuilder>()?.suffix = suffix;
+            Ok(this)
+        });
+        drag_value.add_function(
+            "fixed_decimals",
+            |_, (this, num_decimals): (AnyUserData, usize)| {
+                this.borrow_mut::<DragValueBuilder>()?.fixed_decimals = Some(num_decimals);
+                Ok(this)
+            },
+        );
+    })?;
+    let drag_value = lua.create_table()?;
+    drag_value.set(
+        "new",
+        lua.create_function(|lua, value: Table| {
+            let drag_value = lua.create_any_userdata(DragValueBuilder::default())?;
+            drag_value.set_user_value(value)?;
+            Ok(drag_value)
+        })?,
+    )?;
+    egui_table.set("drag_value", drag_value)?;
+    Ok(())
+}
+fn add_button(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|button: &mut UserDataRegistry<Option<Button<'static>>>| {
+        button.add_function("wrap", |_, (this, wrap): (AnyUserData, bool)| {
+            chain_widget(this, |b: Button<'static>| b.wrap(wrap))
+        });
+        button.add_function("fill", |_, (this, fill): (AnyUserData, Value)| {
+            let fill = Color32::from_lua(fill)?;
+            chain_widget(this, |b: Button<'static>| b.fill(fill))
+        });
+        button.add_function("stroke", |_, (this, stroke): (AnyUserData, Value)| {
+            let stroke = Stroke::from_lua(stroke)?;
+            chain_widget(this, |b: Button<'static>| b.stroke(stroke))
+        });
+        button.add_function("small", |_, this: AnyUserData| {
+            chain_widget(this, |b: Button<'static>| b.small())
+        });
+        button.add_function("frame", |_, (this, frame): (AnyUserData, bool)| {
+            chain_widget(this, |b: Button<'static>| b.frame(frame))
+        });
+        button.add_function("sense", |_, (this, sense): (AnyUserData, Value)| {
+            let sense = Sense::from_lua(sense)?;
+            chain_widget(this, |b: Button<'static>| b.sense(sense))
+        });
+        button.add_function("min_size", |_, (this, min_size): (AnyUserData, Value)| {
+            let min_size = Vec2::from_lua(min_size)?;
+            chain_widget(this, |b: Button<'static>| b.min_size(min_size))
+        });
+        button.add_function("rounding", |_, (this, rounding): (AnyUserData, Value)| {
+            let rounding = Rounding::from_lua(rounding)?;
+            chain_widget(this, |b: Button<'static>| b.rounding(rounding))
+        });
+        button.add_function(
+            "shortcut_text",
+            |lua, (this, shortcut_text): (AnyUserData, Value)| {
+                let shortcut_text = widget_text_from_lua(lua, shortcut_text)?;
+                chain_widget(this, |b: Button<'static>| b.shortcut_text(shortcut_text))
+            },
+        );
+        button.add_function("selected", |_, (this, selected): (AnyUserData, bool)| {
+            chain_widget(this, |b: Button<'static>| b.selected(selected))
+        });
+    })?;
+    let button = lua.create_table()?;
+    button.set(
+        "new",
+        lua.create_function(|lua, text: Value| {
+            lua.create_any_userdata(Some(Button::new(widget_text_from_lua(lua, text)?)))
+        })?,
+    )?;
+    button.set(
+        "image",
+        lua.create_function(|lua, image: UserDataRef<Option<Image<'static>>>| {
+            lua.create_any_userdata(Some(Button::image(image_from_lua(&image)?)))
+        })?,
+    )?;
+    button.set(
+        "image_and_text",
+        lua.create_function(
+            |lua, (image, text): (UserDataRef<Option<Image<'static>>>, Value)| {
+                lua.create_any_userdata(Some(Button::image_and_text(
+                    image_from_lua(&image)?,
+                    widget_text_from_lua(lua, text)?,
+                )))
+            },
+        )?,
+    )?;
+    egui_table.set("button", button)?;
+    Ok(())
+}
+fn add_label(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|label: &mut UserDataRegistry<Option<Label>>| {
+        label.add_function("wrap", |_, (this, wrap): (AnyUserData, bool)| {
+            chain_widget(this, |l: Label| l.wrap(wrap))
+        });
+        label.add_function("truncate", |_, (this, truncate): (AnyUserData, bool)| {
+            chain_widget(this, |l: Label| l.truncate(truncate))
+        });
+        label.add_function(
+            "selectable",
+            |_, (this, selectable): (AnyUserData, bool)| {
+                chain_widget(this, |l: Label| l.selectable(selectable))
+            },
+        );
+        label.add_function("sense", |_, (this, sense): (AnyUserData, Value)| {
+            let sense = Sense::from_lua(sense)?;
+            chain_widget(this, |l: Label| l.sense(sense))
+        });
+    })?;
+    let label = lua.create_table()?;
+    label.set(
+        "new",
+        lua.create_function(|lua, text: Value| {
+            lua.create_any_userdata(Some(Label::new(widget_text_from_lua(lua, text)?)))
+        })?,
+    )?;
+    egui_table.set("label", label)?;
+    Ok(())
+}
+fn add_checkbox(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|checkbox: &mut UserDataRegistry<CheckboxBuilder>| {
+        checkbox.add_function(
+            "indeterminate",
+            |_, (this, indeterminate): (AnyUserData, bool)| {
+                this.borrow_mut::<CheckboxBuilder>()?.indeterminate = indeterminate;
+                Ok(this)
+            },
+        );
+    })?;
+    let checkbox = lua.create_table()?;
+    checkbox.set(
+        "new",
+        lua.create_function(|lua, (value, text): (Table, Value)| {
+            let text = match text {
+                Value::Nil => None,
+                text => Some(widget_text_from_lua(lua, text)?),
+            };
+            let checkbox = lua.create_any_userdata(CheckboxBuilder {
+                text,
+                indeterminate: false,
+            })?;
+            checkbox.set_user_value(value)?;
+            Ok(checkbox)
+        })?,
+    )?;
+    egui_table.set("checkbox", checkbox)?;
+    Ok(())
+}
+fn add_radio_button(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|_: &mut UserDataRegistry<Option<RadioButton>>| {})?;
+    let radio_button = lua.create_table()?;
+    radio_button.set(
+        "new",
+        lua.create_function(|lua, (checked, text): (bool, Value)| {
+            lua.create_any_userdata(Some(RadioButton::new(
+                checked,
+                widget_text_from_lua(lua, text)?,
+            )))
+        })?,
+    )?;
+    egui_table.set("radio_button", radio_button)?;
+    Ok(())
+}
+fn add_selectable_label(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|_: &mut UserDataRegistry<Option<SelectableLabel>>| {})?;
+    let selectable_label = lua.create_table()?;
+    selectable_label.set(
+        "new",
+        lua.create_function(|lua, (selected, text): (bool, Value)| {
+            lua.create_any_userdata(Some(SelectableLabel::new(
+                selected,
+                widget_text_from_lua(lua, text)?,
+            )))
+        })?,
+    )?;
+    egui_table.set("selectable_label", selectable_label)?;
+    Ok(())
+}
+/// Lua only deals in single characters, so we take the first char of the string.
+fn char_from_lua(s: &str) -> Result<char> {
+    s.chars()
+        .next()
+        .ok_or_else(|| mlua::Error::RuntimeError("expected a non-empty string".to_owned()))
+}
+/// epaint panics on font data that it can't parse and on families that name missing fonts,
+/// so we check the definitions before giving them to egui.
+fn check_font_definitions(font_definitions: &FontDefinitions) -> Result<()> {
+    for (family, names) in font_definitions.families.iter() {
+        if let Some(name) = names
+            .iter()
+            .find(|name| !font_definitions.font_data.contains_key(*name))
+        {
+            return Err(mlua::Error::RuntimeError(format!(
+                "font family {family} uses the font {name:?}, which was never inserted"
+            )));
+        }
+    }
+    for (name, data) in font_definitions.font_data.iter() {
+        let font =
+            ab_glyph::FontRef::try_from_slice_and_index(&data.font, data.index).map_err(|e| {
+                mlua::Error::RuntimeError(format!("invalid font data for {name:?}: {e}"))
+            })?;
+        if ab_glyph::Font::units_per_em(&font).is_none() {
+            return Err(mlua::Error::RuntimeError(format!(
+                "the font unit size of {name:?} is out of range"
+            )));
+        }
+    }
+    Ok(())
+}
+fn add_font_definitions(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|font_definitions: &mut UserDataRegistry<FontDefinitions>| {
+        font_definitions.add_method_mut(
+            "insert_font",
+            |_, this, (name, bytes): (String, mlua::String)| {
+                this.font_data
+                    .insert(name, FontData::from_owned(bytes.as_bytes().to_vec()));
+                Ok(())
+            },
+        );
+        // `priority` is the index in the family's fallback list, 0 being tried first.
+        // without it, the font is added as the last fallback.
+        font_definitions.add_method_mut(
+            "push_family",
+            |_, this, (family, name, priority): (Value, String, Option<usize>)| {
+                let fonts = this
+                    .families
+                    .entry(FontFamily::from_lua(family)?)
+                    .or_default();
+                match priority {
+                    Some(priority) => fonts.insert(priority.min(fonts.len()), name),
+                    None => fonts.push(name),
+                }
+                Ok(())
+            },
+        );
+        font_definitions.add_method("font_names", |_, this, ()| {
+            Ok(this.font_data.keys().cloned().collect::<Vec<String>>())
+        });
+        font_definitions.add_method("family", |_, this, family: Value| {
+            Ok(this
+                .families
+                .get(&FontFamily::from_lua(family)?)
+                .cloned()
+                .unwrap_or_default())
+        });
+    })?;
+    let font_definitions = lua.create_table()?;
+    font_definitions.set(
+        "default",
+        lua.create_function(|lua, ()| lua.create_any_userdata(FontDefinitions::default()))?,
+    )?;
+    font_definitions.set(
+        "empty",
+        lua.create_function(|lua, ()| lua.create_any_userdata(FontDefinitions::empty()))?,
+    )?;
+    egui_table.set("font_definitions", font_definitions)?;
+    Ok(())
+}
+fn add_fonts(lua: &Lua) -> Result<()> {
+    lua.register_userdata_type(|fonts: &mut UserDataRegistry<Fonts>| {
+        fonts.add_method(
+            "pixels_per_point",
+            |_, this, ()| Ok(this.pixels_per_point()),
+        );
+        fonts.add_method("row_height", |_, this, font_id: Value| {
+            Ok(this.row_height(&font_id_from_lua(this, font_id)?))
+        });
+        fonts.add_method("glyph_width", |_, this, (font_id, c): (Value, String)| {
+            Ok(this.glyph_width(&font_id_from_lua(this, font_id)?, char_from_lua(&c)?))
+        });
+        fonts.add_method("has_glyph", |_, this, (font_id, c): (Value, String)| {
+            Ok(this.has_glyph(&font_id_from_lua(this, font_id)?, char_from_lua(&c)?))
+        });
+        fonts.add_method("has_glyphs", |_, this, (font_id, s): (Value, String)| {
+            Ok(this.has_glyphs(&font_id_from_lua(this, font_id)?, &s))
+        });
+        fonts.add_method(
+            "layout",
+            |lua, this, (text, font_id, color, wrap_width): (String, Value, Value, f32)| {
+                lua.create_any_userdata(this.layout(
+                    text,
+                    font_id_from_lua(this, font_id)?,
+                    Color32::from_lua(color)?,
+                    wrap_width,
+                ))
+            },
+        );
+        fonts.add_method(
+            "layout_no_wrap",
+            |lua, this, (text, font_id, color): (String, Value, Value)| {
+                lua.create_any_userdata(this.layout_no_wrap(
+                    text,
+                    font_id_from_lua(this, font_id)?,
+                    Color32::from_lua(color)?,
+                ))
+            },
+        );
+        fonts.add_method("layout_job", |lua, this, job: UserDataRef<LayoutJob>| {
+            check_layout_job(&this.families(), &job)?;
+            lua.create_any_userdata(this.layout_job(job.clone()))
+        });
+    })?;
+    Ok(())
+}
+/// Cursors are char indices into the galley's text, same as `TextEditOutput.cursor_range`.
+fn add_galley(lua: &Lua) -> Result<()> {
+    lua.register_userdata_type(|galley: &mut UserDataRegistry<Arc<Galley>>| {
+        galley.add_method("text", |_, this, ()| Ok(this.text().to_owned()));
+        galley.add_method("size", |lua, this, ()| Vec2::to_lua(this.size(), lua));
+        galley.add_method("rect", |lua, this, ()| Rect::to_lua(this.rect, lua));
+        galley.add_method("is_empty", |_, this, ()| Ok(this.is_empty()));
+        galley.add_method("elided", |_, this, ()| Ok(this.elided));
+        galley.add_method("rows", |lua, this, ()| {
+            let rows = lua.create_table()?;
+            for row in this.rows.iter() {
+                let r = lua.create_table()?;
+                r.set("rect", Rect::to_lua(row.rect, lua)?)?;
+                r.set("char_count", row.char_count_excluding_newline())?;
+                r.set("ends_with_newline", row.ends_with_newline)?;
+                rows.push(r)?;
+            }
+            Ok(rows)
+        });
+        galley.add_method("pos_from_cursor", |lua, this, index: usize| {
+            Rect::to_lua(this.pos_from_ccursor(CCursor::new(index)), lua)
+        });
+        galley.add_method("cursor_from_pos", |_, this, pos: Value| {
+            Ok(this.cursor_from_pos(Vec2::from_lua(pos)?).ccursor.index)
+        });
+    })?;
+    Ok(())
+}
+fn add_grid(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|grid: &mut UserDataRegistry<Option<Grid>>| {
+        grid.add_function(
+            "num_columns",
+            |_, (this, num_columns): (AnyUserData, usize)| {
+                chain_widget(this, |g: Grid| g.num_columns(num_columns))
+            },
+        );
+        grid.add_function("striped", |_, (this, striped): (AnyUserData, bool)| {
+            chain_widget(this, |g: Grid| g.striped(striped))
+        });
+        grid.add_function(
+            "min_col_width",
+            |_, (this, min_col_width): (AnyUserData, f32)| {
+                chain_widget(this, |g: Grid| g.min_col_width(min_col_width))
+            },
+        );
+        grid.add_function(
+            "max_col_width",
+            |_, (this, max_col_width): (AnyUserData, f32)| {
+                chain_widget(this, |g: Grid| g.max_col_width(max_col_width))
+            },
+        );
+        grid.add_function(
+            "min_row_height",
+            |_, (this, min_row_height): (AnyUserData, f32)| {
+                chain_widget(this, |g: Grid| g.min_row_height(min_row_height))
+            },
+        );
+        grid.add_function("spacing", |_, (this, spacing): (AnyUserData, Value)| {
+            let spacing = Vec2::from_lua(spacing)?;
+            chain_widget(this, |g: Grid| g.spacing(spacing))
+        });
+        grid.add_function("start_row", |_, (this, start_row): (AnyUserData, usize)| {
+            chain_widget(this, |g: Grid| g.start_row(start_row))
+        });
+        grid.add_function(
+            "show",
+            |lua, (this, mut ui, add_contents): (AnyUserData, UserDataRefMut<Ui>, Function)| {
+                let grid = this
+                    .borrow_mut::<Option<Grid>>()?
+                    .take()
+                    .ok_or_else(|| mlua::Error::RuntimeError("grid is null".to_owned()))?;
+                let ir = grid.show(&mut ui, |ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_contents.call(ui);
+                        result
+                    })
+                });
+                let r = lua.create_any_userdata(ir.response)?;
+                let mut i = ir.inner?;
+                i.push_front(Value::UserData(r));
+                Ok(i)
+            },
+        );
+    })?;
+    let grid = lua.create_table()?;
+    grid.set(
+        "new",
+        lua.create_function(|lua, id_source: Value| {
+            lua.create_any_userdata(Some(Grid::new(LuaHashable::from_lua(id_source)?)))
+        })?,
+    )?;
+    egui_table.set("grid", grid)?;
+    Ok(())
+}
+fn add_hyperlink(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|hyperlink: &mut UserDataRegistry<Option<Hyperlink>>| {
+        hyperlink.add_function(
+            "open_in_new_tab",
+            |_, (this, new_tab): (AnyUserData, bool)| {
+                chain_widget(this, |h: Hyperlink| h.open_in_new_tab(new_tab))
+            },
+        );
+    })?;
+    let hyperlink = lua.create_table()?;
+    hyperlink.set(
+        "new",
+        lua.create_function(|lua, url: String| lua.create_any_userdata(Some(Hyperlink::new(url))))?,
+    )?;
+    hyperlink.set(
+        "from_label_and_url",
+        lua.create_function(|lua, (text, url): (Value, String)| {
+            lua.create_any_userdata(Some(Hyperlink::from_label_and_url(
+                widget_text_from_lua(lua, text)?,
+                url,
+            )))
+        })?,
+    )?;
+    egui_table.set("hyperlink", hyperlink)?;
+    Ok(())
+}
+fn add_layout_job(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|layout_job: &mut UserDataRegistry<LayoutJob>| {
+        layout_job.add_field_method_get("text", |_, this| Ok(this.text.clone()));
+        layout_job.add_field_method_get("wrap", |lua, this| {
+            TextWrapping::to_lua(this.wrap.clone(), lua)
+        });
+        layout_job.add_field_method_get("first_row_min_height", |_, this| {
+            Ok(this.first_row_min_height)
+        });
+        layout_job.add_field_method_get("break_on_newline", |_, this| Ok(this.break_on_newline));
+        layout_job.add_field_method_get("halign", |lua, this| Align::to_lua(this.halign, lua));
+        layout_job.add_field_method_get("justify", |_, this| Ok(this.justify));
+        layout_job.add_field_method_set("wrap", |_, this, wrap: Value| {
+            this.wrap = TextWrapping::from_lua(wrap)?;
+            Ok(())
+        });
+        layout_job.add_field_method_set("first_row_min_height", |_, this, height: f32| {
+            this.first_row_min_height = height;
+            Ok(())
+        });
+        layout_job.add_field_method_set("break_on_newline", |_, this, break_on_newline: bool| {
+            this.break_on_newline = break_on_newline;
+            Ok(())
+        });
+        layout_job.add_field_method_set("halign", |_, this, halign: Value| {
+            this.halign = Align::from_lua(halign)?;
+            Ok(())
+        });
+        layout_job.add_field_method_set("justify", |_, this, justify: bool| {
+            this.justify = justify;
+            Ok(())
+        });
+        layout_job.add_method_mut(
+            "append",
+            |lua, this, (text, leading_space, format): (String, f32, Value)| {
+                this.append(&text, leading_space, text_format_from_lua(lua, format)?);
+                Ok(())
+            },
+        );
+        layout_job.add_method("is_empty", |_, this, ()| Ok(this.is_empty()));
+    })?;
+    let layout_job = lua.create_table()?;
+    layout_job.set(
+        "new",
+        lua.create_function(|lua, ()| lua.create_any_userdata(LayoutJob::default()))?,
+    )?;
+    layout_job.set(
+        "simple",
+        lua.create_function(
+            |lua, (text, font_id, color, wrap_width): (String, Value, Value, f32)| {
+                lua.create_any_userdata(LayoutJob::simple(
+                    text,
+                    known_font_id_from_lua(lua, font_id)?,
+                    Color32::from_lua(color)?,
+                    wrap_width,
+                ))
+            },
+        )?,
+    )?;
+    layout_job.set(
+        "simple_singleline",
+        lua.create_function(|lua, (text, font_id, color): (String, Value, Value)| {
+            lua.create_any_userdata(LayoutJob::simple_singleline(
+                text,
+                known_font_id_from_lua(lua, font_id)?,
+                Color32::from_lua(color)?,
+            ))
+        })?,
+    )?;
+    layout_job.set(
+        "single_section",
+        lua.create_function(|lua, (text, format): (String, Value)| {
+            lua.create_any_userdata(LayoutJob::single_section(
+                text,
+                text_format_from_lua(lua, format)?,
+            ))
+        })?,
+    )?;
+    egui_table.set("layout_job", layout_job)?;
+    Ok(())
+}
+fn add_link(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|_: &mut UserDataRegistry<Option<Link>>| {})?;
+    let link = lua.create_table()?;
+    link.set(
+        "new",
+        lua.create_function(|lua, text: Value| {
+            lua.create_any_userdata(Some(Link::new(widget_text_from_lua(lua, text)?)))
+        })?,
+    )?;
+    egui_table.set("link", link)?;
+    Ok(())
+}
+fn add_separator(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|separator: &mut UserDataRegistry<Option<Separator>>| {
+        separator.add_function("spacing", |_, (this, spacing): (AnyUserData, f32)| {
+            chain_widget(this, |s: Separator| s.spacing(spacing))
+        });
+        separator.add_function("horizontal", |_, this: AnyUserData| {
+            chain_widget(this, |s: Separator| s.horizontal())
+        });
+        separator.add_function("vertical", |_, this: AnyUserData| {
+            chain_widget(this, |s: Separator| s.vertical())
+        });
+        separator.add_function("grow", |_, (this, extra): (AnyUserData, f32)| {
+            chain_widget(this, |s: Separator| s.grow(extra))
+        });
+        separator.add_function("shrink", |_, (this, shrink): (AnyUserData, f32)| {
+            chain_widget(this, |s: Separator| s.shrink(shrink))
+        });
+    })?;
+    let separator = lua.create_table()?;
+    separator.set(
+        "new",
+        lua.create_function(|lua, ()| lua.create_any_userdata(Some(Separator::default())))?,
+    )?;
+    egui_table.set("separator", separator)?;
+    Ok(())
+}
+fn add_spinner(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|spinner: &mut UserDataRegistry<Option<Spinner>>| {
+        spinner.add_function("size", |_, (this, size): (AnyUserData, f32)| {
+            chain_widget(this, |s: Spinner| s.size(size))
+        });
+        spinner.add_function("color", |_, (this, color): (AnyUserData, Value)| {
+            let color = Color32::from_lua(color)?;
+            chain_widget(this, |s: Spinner| s.color(color))
+        });
+    })?;
+    let spinner = lua.create_table()?;
+    spinner.set(
+        "new",
+        lua.create_function(|lua, ()| lua.create_any_userdata(Some(Spinner::new())))?,
+    )?;
+    egui_table.set("spinner", spinner)?;
+    Ok(())
+}
+fn add_menu(lua: &Lua, egui_table: &Table) -> Result<()> {
+    let menu = lua.create_table()?;
+    menu.set(
+        "bar",
+        lua.create_function(
+            |lua, (mut ui, add_contents): (UserDataRefMut<Ui>, Function)| {
+                let ir = menu::bar(&mut ui, |ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_contents.call(ui);
+                        result
+                    })
+                });
+                let r = lua.create_any_userdata(ir.response)?;
+                let mut i = ir.inner?;
+                i.push_front(Value::UserData(r));
+                Ok(i)
+            },
+        )?,
+    )?;
+    menu.set(
+        "menu_button",
+        lua.create_function(
+            |lua, (mut ui, title, add_contents): (UserDataRefMut<Ui>, Value, Function)| {
+                let ir = menu::menu_button(&mut ui, widget_text_from_lua(lua, title)?, |ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_contents.call(ui);
+                        result
+                    })
+                });
+                let r = lua.create_any_userdata(ir.response)?;
+                let mut i = ir.inner.transpose()?.unwrap_or_default();
+                i.push_front(Value::UserData(r));
+                Ok(i)
+            },
+        )?,
+    )?;
+    menu.set(
+        "menu_image_button",
+        lua.create_function(
+            |lua,
+             (mut ui, image, add_contents): (
+                UserDataRefMut<Ui>,
+                UserDataRef<Option<Image<'static>>>,
+                Function,
+            )| { show_menu_image_button(lua, &mut ui, &image, add_contents) },
+        )?,
+    )?;
+    egui_table.set("menu", menu)?;
+    Ok(())
+}
+/// Backs both `ui:menu_image_button` and `egui.menu.menu_image_button`.
+/// The image is cloned, so the same `egui.image` can be used every frame.
+fn show_menu_image_button<'lua>(
+    lua: &'lua Lua,
+    ui: &mut Ui,
+    image: &Option<Image<'static>>,
+    add_contents: Function<'lua>,
+) -> Result<MultiValue<'lua>> {
+    let ir = ui.menu_image_button(image_from_lua(image)?, |ui| {
+        lua.scope(|scope| {
+            let ui = scope.create_any_userdata_ref_mut(ui)?;
+            let result: Result<MultiValue> = add_contents.call(ui);
+            result
+        })
+    });
+    let r = lua.create_any_userdata(ir.response)?;
+    let mut i = ir.inner.transpose()?.unwrap_or_default();
+    i.push_front(Value::UserData(r));
+    Ok(i)
+}
+fn add_popup(lua: &Lua, egui_table: &Table) -> Result<()> {
+    let popup = lua.create_table()?;
+    popup.set(
+        "below_widget",
+        lua.create_function(
+            |lua,
+             (ui, popup_id, widget_response, add_contents): (
+                UserDataRef<Ui>,
+                Value,
+                UserDataRef<egui::Response>,
+                Function,
+            )| {
+                popup_below_widget(&ui, Id::from_lua(popup_id)?, &widget_response, |ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_contents.call(ui);
+                        result
+                    })
+                })
+                .transpose()
+                .map(Option::unwrap_or_default)
+            },
+        )?,
+    )?;
+    popup.set(
+        "above_or_below_widget",
+        lua.create_function(
+            |lua,
+             (ui, popup_id, widget_response, above_or_below, add_contents): (
+                UserDataRef<Ui>,
+                Value,
+                UserDataRef<egui::Response>,
+                Value,
+                Function,
+            )| {
+                popup_above_or_below_widget(
+                    &ui,
+                    Id::from_lua(popup_id)?,
+                    &widget_response,
+                    AboveOrBelow::from_lua(above_or_below)?,
+                    |ui| {
+                        lua.scope(|scope| {
+                            let ui = scope.create_any_userdata_ref_mut(ui)?;
+                            let result: Result<MultiValue> = add_contents.call(ui);
+                            result
+                        })
+                    },
+                )
+                .transpose()
+                .map(Option::unwrap_or_default)
+            },
+        )?,
+    )?;
+    egui_table.set("popup", popup)?;
+    Ok(())
+}
+fn add_texture_handle(lua: &Lua) -> Result<()> {
+    lua.register_userdata_type(|texture_handle: &mut UserDataRegistry<TextureHandle>| {
+        texture_handle.add_method("id", |lua, this, ()| lua.create_any_userdata(this.id()));
+        texture_handle.add_method("name", |_, this, ()| Ok(this.name()));
+        texture_handle.add_method("size", |lua, this, ()| Vec2::to_lua(this.size_vec2(), lua));
+        texture_handle.add_method("aspect_ratio", |_, this, ()| Ok(this.aspect_ratio()));
+        texture_handle.add_method_mut(
+            "set",
+            |_, this, (image, options): (UserDataRef<ColorImage>, Value)| {
+                this.set(image.clone(), TextureOptions::from_lua(options)?);
+                Ok(())
+            },
+        );
+    })?;
+    Ok(())
+}
+fn add_tooltip(lua: &Lua, egui_table: &Table) -> Result<()> {
+    let tooltip = lua.create_table()?;
+    tooltip.set(
+        "show_at_pointer",
+        lua.create_function(
+            |lua, (ctx, id, add_contents): (UserDataRef<Context>, Value, Function)| {
+                show_tooltip_at_pointer(&ctx, Id::from_lua(id)?, |ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_contents.call(ui);
+                        result
+                    })
+                })
+                .transpose()
+                .map(Option::unwrap_or_default)
+            },
+        )?,
+    )?;
+    tooltip.set(
+        "show_at",
+        lua.create_function(
+            |lua,
+             (ctx, id, suggested_position, add_contents): (
+                UserDataRef<Context>,
+                Value,
+                Value,
+                Function,
+            )| {
+                let suggested_position = match suggested_position {
+                    Value::Nil => None,
+                    pos => Some(Pos2::from_lua(pos)?),
+                };
+                show_tooltip_at(&ctx, Id::from_lua(id)?, suggested_position, |ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_contents.call(ui);
+                        result
+                    })
+                })
+                .transpose()
+                .map(Option::unwrap_or_default)
+            },
+        )?,
+    )?;
+    tooltip.set(
+        "show_for",
+        lua.create_function(
+            |lua, (ctx, id, rect, add_contents): (UserDataRef<Context>, Value, Value, Function)| {
+                show_tooltip_for(&ctx, Id::from_lua(id)?, &Rect::from_lua(rect)?, |ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_contents.call(ui);
+                        result
+                    })
+                })
+                .transpose()
+                .map(Option::unwrap_or_default)
+            },
+        )?,
+    )?;
+    tooltip.set(
+        "show_text",
+        lua.create_function(
+            |lua, (ctx, id, text): (UserDataRef<Context>, Value, Value)| {
+                Ok(
+                    show_tooltip_text(&ctx, Id::from_lua(id)?, widget_text_from_lua(lua, text)?)
+                        .is_some(),
+                )
+            },
+        )?,
+    )?;
+    tooltip.set(
+        "was_open_last_frame",
+        lua.create_function(|_, (ctx, id): (UserDataRef<Context>, Value)| {
+            Ok(was_tooltip_open_last_frame(&ctx, Id::from_lua(id)?))
+        })?,
+    )?;
+    egui_table.set("tooltip", tooltip)?;
+    Ok(())
+}
+fn add_progress_bar(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|progress_bar: &mut UserDataRegistry<Option<ProgressBar>>| {
+        progress_bar.add_function(
+            "desired_width",
+            |_, (this, desired_width): (AnyUserData, f32)| {
+                chain_widget(this, |p: ProgressBar| p.desired_width(desired_width))
+            },
+        );
+        progress_bar.add_function(
+            "desired_height",
+            |_, (this, desired_height): (AnyUserData, f32)| {
+                chain_widget(this, |p: ProgressBar| p.desired_height(desired_height))
+            },
+        );
+        progress_bar.add_function("fill", |_, (this, fill): (AnyUserData, Value)| {
+            let fill = Color32::from_lua(fill)?;
+            chain_widget(this, |p: ProgressBar| p.fill(fill))
+        });
+        progress_bar.add_function("text", |lua, (this, text): (AnyUserData, Value)| {
+            let text = widget_text_from_lua(lua, text)?;
+            chain_widget(this, |p: ProgressBar| p.text(text))
+        });
+        progress_bar.add_function("show_percentage", |_, this: AnyUserData| {
+            chain_widget(this, |p: ProgressBar| p.show_percentage())
+        });
+        progress_bar.add_function("animate", |_, (this, animate): (AnyUserData, bool)| {
+            chain_widget(this, |p: ProgressBar| p.animate(animate))
+        });
+        progress_bar.add_function("rounding", |_, (this, rounding): (AnyUserData, Value)| {
+            let rounding = Rounding::from_lua(rounding)?;
+            chain_widget(this, |p: ProgressBar| p.rounding(rounding))
+        });
+    })?;
+    let progress_bar = lua.create_table()?;
+    progress_bar.set(
+        "new",
+        lua.create_function(|lua, progress: f32| {
+            lua.create_any_userdata(Some(ProgressBar::new(progress)))
+        })?,
+    )?;
+    egui_table.set("progress_bar", progress_bar)?;
+    Ok(())
+}
+fn add_text_edit(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|text_edit: &mut UserDataRegistry<TextEditBuilder>| {
+        text_edit.add_function("code_editor", |_, this: AnyUserData| {
+            this.borrow_mut::<TextEditBuilder>()?.code_editor = true;
+            Ok(this)
+        });
+        text_edit.add_function("id", |_, (this, id): (AnyUserData, Value)| {
+            this.borrow_mut::<TextEditBuilder>()?.id = Some(Id::from_lua(id)?);
+            Ok(this)
+        });
+        text_edit.add_function("id_source", |_, (this, id_source): (AnyUserData, Value)| {
+            this.borrow_mut::<TextEditBuilder>()?.id_source =
+                Some(Id::new(LuaHashable::from_lua(id_source)?));
+            Ok(this)
+        });
+        text_edit.add_function(
+            "hint_text",
+            |lua, (this, hint_text): (AnyUserData, Value)| {
+                this.borrow_mut::<TextEditBuilder>()?.hint_text =
+                    Some(widget_text_from_lua(lua, hint_text)?);
+                Ok(this)
+            },
+        );
+        text_edit.add_function("password", |_, (this, password): (AnyUserData, bool)| {
+            this.borrow_mut::<TextEditBuilder>()?.password = Some(password);
+            Ok(this)
+        });
+        // takes a font id table or a text style
+        text_edit.add_function("font", |lua, (this, font): (AnyUserData, Value)| {
+            let font = match font {
+                Value::Table(_) => {
+                    let font_id = FontId::from_lua(font)?;
+                    with_known_fonts(lua, |known| {
+                        check_font_family(&known.families, &font_id.family)
+                    })?;
+                    FontSelection::FontId(font_id)
+                }
+                _ => {
+                    let text_style = TextStyle::from_lua(font)?;
+                    with_known_fonts(lua, |known| {
+                        check_text_style(&known.text_styles, &text_style)
+                    })?;
+                    FontSelection::Style(text_style)
+                }
+            };
+            this.borrow_mut::<TextEditBuilder>()?.font = Some(font);
+            Ok(this)
+        });
+        text_edit.add_function("text_color", |_, (this, color): (AnyUserData, Value)| {
+            this.borrow_mut::<TextEditBuilder>()?.text_color = Some(Color32::from_lua(color)?);
+            Ok(this)
+        });
+        text_edit.add_function(
+            "interactive",
+            |_, (this, interactive): (AnyUserData, bool)| {
+                this.borrow_mut::<TextEditBuilder>()?.interactive = Some(interactive);
+                Ok(this)
+            },
+        );
+        text_edit.add_function("frame", |_, (this, frame): (AnyUserData, bool)| {
+            this.borrow_mut::<TextEditBuilder>()?.frame = Some(frame);
+            Ok(this)
+        });
+        text_edit.add_function("margin", |_, (this, margin): (AnyUserData, Value)| {
+            this.borrow_mut::<TextEditBuilder>()?.margin = Some(Vec2::from_lua(margin)?);
+            Ok(this)
+        });
+        text_edit.add_function(
+            "desired_width",
+            |_, (this, desired_width): (AnyUserData, f32)| {
+                this.borrow_mut::<TextEditBuilder>()?.desired_width = Some(desired_width);
+                Ok(this)
+            },
+        );
+        text_edit.add_function(
+            "desired_rows",
+            |_, (this, desired_rows): (AnyUserData, usize)| {
+                this.borrow_mut::<TextEditBuilder>()?.desired_rows = Some(desired_rows);
+                Ok(this)
+            },
+        );
+        text_edit.add_function(
+            "lock_focus",
+            |_, (this, lock_focus): (AnyUserData, bool)| {
+                this.borrow_mut::<TextEditBuilder>()?.lock_focus = Some(lock_focus);
+                Ok(this)
+            },
+        );
+        text_edit.add_function(
+            "cursor_at_end",
+            |_, (this, cursor_at_end): (AnyUserData, bool)| {
+                this.borrow_mut::<TextEditBuilder>()?.cursor_at_end = Some(cursor_at_end);
+                Ok(this)
+            },
+        );
+        text_edit.add_function("clip_text", |_, (this, clip_text): (AnyUserData, bool)| {
+            this.borrow_mut::<TextEditBuilder>()?.clip_text = Some(clip_text);
+            Ok(this)
+        });
+        text_edit.add_function("char_limit", |_, (this, limit): (AnyUserData, usize)| {
+            this.borrow_mut::<TextEditBuilder>()?.char_limit = Some(limit);
+            Ok(this)
+        });
+        text_edit.add_function(
+            "horizontal_align",
+            |_, (this, align): (AnyUserData, Value)| {
+                this.borrow_mut::<TextEditBuilder>()?.horizontal_align =
+                    Some(Align::from_lua(align)?);
+                Ok(this)
+            },
+        );
+        text_edit.add_function(
+            "vertical_align",
+            |_, (this, align): (AnyUserData, Value)| {
+                this.borrow_mut::<TextEditBuilder>()?.vertical_align =
+                    Some(Align::from_lua(align)?);
+                Ok(this)
+            },
+        );
+        text_edit.add_function("min_size", |_, (this, min_size): (AnyUserData, Value)| {
+            this.borrow_mut::<TextEditBuilder>()?.min_size = Some(Vec2::from_lua(min_size)?);
+            Ok(this)
+        });
+        text_edit.add_function(
+            "layouter",
+            |_, (this, layouter): (AnyUserData, Function)| {
+                this.set_nth_user_value(2, layouter)?;
+                Ok(this)
+            },
+        );
+        text_edit.add_function(
+            "show",
+            |lua, (this, mut ui): (AnyUserData, UserDataRefMut<Ui>)| {
+                let output = show_text_edit(lua, &this, |text_edit| text_edit.show(&mut ui))?;
+                text_edit_output_to_lua(lua, output)
+            },
+        );
+    })?;
+    let text_edit = lua.create_table()?;
+    text_edit.set(
+        "singleline",
+        lua.create_function(|lua, value: Table| {
+            let text_edit = lua.create_any_userdata(TextEditBuilder::default())?;
+            text_edit.set_user_value(value)?;
+            Ok(text_edit)
+        })?,
+    )?;
+    text_edit.set(
+        "multiline",
+        lua.create_function(|lua, value: Table| {
+            let text_edit = lua.create_any_userdata(TextEditBuilder {
+                multiline: true,
+                ..Default::default()
+            })?;
+            text_edit.set_user_value(value)?;
+            Ok(text_edit)
+        })?,
+    )?;
+    egui_table.set("text_edit", text_edit)?;
+    Ok(())
+}
+/// `Image` is cheap to clone, so buttons take a copy instead of consuming the image userdata.
+fn image_from_lua(image: &Option<Image<'static>>) -> Result<Image<'static>> {
+    image
+        .clone()
+        .ok_or_else(|| mlua::Error::RuntimeError("image is null".to_owned()))
+}
+fn add_image(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|image: &mut UserDataRegistry<Option<Image<'static>>>| {
+        image.add_function("max_width", |_, (this, width): (AnyUserData, f32)| {
+            chain_widget(this, |i: Image<'static>| i.max_width(width))
+        });
+        image.add_function("max_height", |_, (this, height): (AnyUserData, f32)| {
+            chain_widget(this, |i: Image<'static>| i.max_height(height))
+        });
+        image.add_function("max_size", |_, (this, size): (AnyUserData, Value)| {
+            let size = Vec2::from_lua(size)?;
+            chain_widget(this, |i: Image<'static>| i.max_size(size))
+        });
+        image.add_function(
+            "maintain_aspect_ratio",
+            |_, (this, value): (AnyUserData, bool)| {
+                chain_widget(this, |i: Image<'static>| i.maintain_aspect_ratio(value))
+            },
+        );
+        image.add_function(
+            "fit_to_original_size",
+            |_, (this, scale): (AnyUserData, f32)| {
+                chain_widget(this, |i: Image<'static>| i.fit_to_original_size(scale))
+            },
+        );
+        image.add_function(
+            "fit_to_exact_size",
+            |_, (this, size): (AnyUserData, Value)| {
+                let size = Vec2::from_lua(size)?;
+                chain_widget(this, |i: Image<'static>| i.fit_to_exact_size(size))
+            },
+        );
+        image.add_function(
+            "fit_to_fraction",
+            |_, (this, fraction): (AnyUserData, Value)| {
+                let fraction = Vec2::from_lua(fraction)?;
+                chain_widget(this, |i: Image<'static>| i.fit_to_fraction(fraction))
+            },
+        );
+        image.add_function("shrink_to_fit", |_, this: AnyUserData| {
+            chain_widget(this, |i: Image<'static>| i.shrink_to_fit())
+        });
+        image.add_function("sense", |_, (this, sense): (AnyUserData, Value)| {
+            let sense = Sense::from_lua(sense)?;
+            chain_widget(this, |i: Image<'static>| i.sense(sense))
+        });
+        image.add_function("uv", |_, (this, uv): (AnyUserData, Value)| {
+            let uv = Rect::from_lua(uv)?;
+            chain_widget(this, |i: Image<'static>| i.uv(uv))
+        });
+        image.add_function("bg_fill", |_, (this, bg_fill): (AnyUserData, Value)| {
+            let bg_fill = Color32::from_lua(bg_fill)?;
+            chain_widget(this, |i: Image<'static>| i.bg_fill(bg_fill))
+        });
+        image.add_function("tint", |_, (this, tint): (AnyUserData, Value)| {
+            let tint = Color32::from_lua(tint)?;
+            chain_widget(this, |i: Image<'static>| i.tint(tint))
+        });
+        image.add_function(
+            "rotate",
+            |_, (this, angle, origin): (AnyUserData, f32, Value)| {
+                let origin = Vec2::from_lua(origin)?;
+                chain_widget(this, |i: Image<'static>| i.rotate(angle, origin))
+            },
+        );
+        image.add_function("rounding", |_, (this, rounding): (AnyUserData, Value)| {
+            let rounding = Rounding::from_lua(rounding)?;
+            chain_widget(this, |i: Image<'static>| i.rounding(rounding))
+        });
+        image.add_function(
+            "show_loading_spinner",
+            |_, (this, show): (AnyUserData, bool)| {
+                chain_widget(this, |i: Image<'static>| i.show_loading_spinner(show))
+            },
+        );
+    })?;
+    let image = lua.create_table()?;
+    image.set(
+        "new",
+        lua.create_function(
+            |lua, (texture, size): (UserDataRef<TextureHandle>, Value)| {
+                lua.create_any_userdata(Some(Image::new(SizedTexture::new(
+                    texture.id(),
+                    Vec2::from_lua(size)?,
+                ))))
+            },
+        )?,
+    )?;
+    image.set(
+        "from_uri",
+        lua.create_function(|lua, uri: String| {
+            lua.create_any_userdata(Some(Image::from_uri(uri)))
+        })?,
+    )?;
+    egui_table.set("image", image)?;
+    Ok(())
+}
+fn add_image_button(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(
+        |image_button: &mut UserDataRegistry<Option<ImageButton<'static>>>| {
+            image_button.add_function("uv", |_, (this, uv): (AnyUserData, Value)| {
+                let uv = Rect::from_lua(uv)?;
+                chain_widget(this, |b: ImageButton<'static>| b.uv(uv))
+            });
+            image_button.add_function("tint", |_, (this, tint): (AnyUserData, Value)| {
+                let tint = Color32::from_lua(tint)?;
+                chain_widget(this, |b: ImageButton<'static>| b.tint(tint))
+            });
+            image_button.add_function("selected", |_, (this, selected): (AnyUserData, bool)| {
+                chain_widget(this, |b: ImageButton<'static>| b.selected(selected))
+            });
+            image_button.add_function("frame", |_, (this, frame): (AnyUserData, bool)| {
+                chain_widget(this, |b: ImageButton<'static>| b.frame(frame))
+            });
+            image_button.add_function("sense", |_, (this, sense): (AnyUserData, Value)| {
+                let sense = Sense::from_lua(sense)?;
+                chain_widget(this, |b: ImageButton<'static>| b.sense(sense))
+            });
+            image_button.add_function("rounding", |_, (this, rounding): (AnyUserData, Value)| {
+                let rounding = Rounding::from_lua(rounding)?;
+                chain_widget(this, |b: ImageButton<'static>| b.rounding(rounding))
+            });
+        },
+    )?;
+    let image_button = lua.create_table()?;
+    image_button.set(
+        "new",
+        lua.create_function(|lua, image: UserDataRef<Option<Image<'static>>>| {
+            lua.create_any_userdata(Some(ImageButton::new(image_from_lua(&image)?)))
+        })?,
+    )?;
+    egui_table.set("image_button", image_button)?;
+    Ok(())
+}
+fn scroll_area_output_to_lua<'lua>(
+    lua: &'lua Lua,
+    output: ScrollAreaOutput<Result<MultiValue<'lua>>>,
+) -> Result<MultiValue<'lua>> {
+    let table = lua.create_table()?;
+    table.set("id", lua.create_any_userdata(output.id)?)?;
+    table.set("offset", Vec2::to_lua(output.state.offset, lua)?)?;
+    table.set("velocity", Vec2::to_lua(output.state.velocity(), lua)?)?;
+    table.set("content_size", Vec2::to_lua(output.content_size, lua)?)?;
+    table.set("inner_rect", Rect::to_lua(output.inner_rect, lua)?)?;
+    let mut i = output.inner?;
+    i.push_front(Value::Table(table));
+    Ok(i)
+}
+fn add_scroll_area(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|scroll_area: &mut UserDataRegistry<Option<ScrollArea>>| {
+        scroll_area.add_function("max_width", |_, (this, max_width): (AnyUserData, f32)| {
+            chain_widget(this, |s: ScrollArea| s.max_width(max_width))
+        });
+        scroll_area.add_function("max_height", |_, (this, max_height): (AnyUserData, f32)| {
+            chain_widget(this, |s: ScrollArea| s.max_height(max_height))
+        });
+        scroll_area.add_function(
+            "min_scrolled_width",
+            |_, (this, min_scrolled_width): (AnyUserData, f32)| {
+                chain_widget(this, |s: ScrollArea| {
+                    s.min_scrolled_width(min_scrolled_width)
+                })
+            },
+        );
+        scroll_area.add_function(
+            "min_scrolled_height",
+            |_, (this, min_scrolled_height): (AnyUserData, f32)| {
+                chain_widget(this, |s: ScrollArea| {
+                    s.min_scrolled_height(min_scrolled_height)
+                })
+            },
+        );
+        scroll_area.add_function("id_source", |_, (this, id_source): (AnyUserData, Value)| {
+            let id_source = LuaHashable::from_lua(id_source)?;
+            chain_widget(this, |s: ScrollArea| s.id_source(id_source))
+        });
+        scroll_area.add_function(
+            "scroll_offset",
+            |_, (this, offset): (AnyUserData, Value)| {
+                let offset = Vec2::from_lua(offset)?;
+                chain_widget(this, |s: ScrollArea| s.scroll_offset(offset))
+            },
+        );
+        scroll_area.add_function(
+            "vertical_scroll_offset",
+            |_, (this, offset): (AnyUserData, f32)| {
+                chain_widget(this, |s: ScrollArea| s.vertical_scroll_offset(offset))
+            },
+        );
+        scroll_area.add_function(
+            "horizontal_scroll_offset",
+            |_, (this, offset): (AnyUserData, f32)| {
+                chain_widget(this, |s: ScrollArea| s.horizontal_scroll_offset(offset))
+            },
+        );
+        scroll_area.add_function("hscroll", |_, (this, hscroll): (AnyUserData, bool)| {
+            chain_widget(this, |s: ScrollArea| s.hscroll(hscroll))
+        });
+        scroll_area.add_function("vscroll", |_, (this, vscroll): (AnyUserData, bool)| {
+            chain_widget(this, |s: ScrollArea| s.vscroll(vscroll))
+        });
+        scroll_area.add_function(
+            "enable_scrolling",
+            |_, (this, enable): (AnyUserData, bool)| {
+                chain_widget(this, |s: ScrollArea| s.enable_scrolling(enable))
+            },
+        );
+        scroll_area.add_function(
+            "drag_to_scroll",
+            |_, (this, drag_to_scroll): (AnyUserData, bool)| {
+                chain_widget(this, |s: ScrollArea| s.drag_to_scroll(drag_to_scroll))
+            },
+        );
+        // a single bool applies to both axes
+        scroll_area.add_function(
+            "auto_shrink",
+            |_, (this, x, y): (AnyUserData, bool, Option<bool>)| {
+                chain_widget(this, |s: ScrollArea| s.auto_shrink([x, y.unwrap_or(x)]))
+            },
+        );
+        scroll_area.add_function("stick_to_right", |_, (this, stick): (AnyUserData, bool)| {
+            chain_widget(this, |s: ScrollArea| s.stick_to_right(stick))
+        });
+        scroll_area.add_function(
+            "stick_to_bottom",
+            |_, (this, stick): (AnyUserData, bool)| {
+                chain_widget(this, |s: ScrollArea| s.stick_to_bottom(stick))
+            },
+        );
+        scroll_area.add_function(
+            "show",
+            |lua, (this, mut ui, add_contents): (AnyUserData, UserDataRefMut<Ui>, Function)| {
+                let scroll_area = this
+                    .borrow_mut::<Option<ScrollArea>>()?
+                    .take()
+                    .ok_or_else(|| mlua::Error::RuntimeError("scroll area is null".to_owned()))?;
+                let output = scroll_area.show(&mut ui, |ui| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> = add_contents.call(ui);
+                        result
+                    })
+                });
+                scroll_area_output_to_lua(lua, output)
             },
-            _ => {
-                return Err(mlua::Error::FromLuaConversionError {
-                    from: "luavalue",
-                    to: "pointerbutton",
-                    message: None,
-                })
-            }
-        })
-    }
-
-    fn to_lua(value: Self, _lua: &Lua) -> Result<Value> {
-        Ok(Value::Integer(match value {
-            Self::LeftToRight => 0,
-            Self::RightToLeft => 1,
-            Self::TopDown => 2,
-            Self::BottomUp => 3,
-        }))
-    }
+        );
+        // `add_contents` gets the visible rows as the range `[min_row, max_row)` and
+        // is only called once per frame, so long lists don't call into lua per row.
+        scroll_area.add_function(
+            "show_rows",
+            |lua,
+             (this, mut ui, row_height, total_rows, add_contents): (
+                AnyUserData,
+                UserDataRefMut<Ui>,
+                f32,
+                usize,
+                Function,
+            )| {
+                let scroll_area = this
+                    .borrow_mut::<Option<ScrollArea>>()?
+                    .take()
+                    .ok_or_else(|| mlua::Error::RuntimeError("scroll area is null".to_owned()))?;
+                let output = scroll_area.show_rows(&mut ui, row_height, total_rows, |ui, rows| {
+                    lua.scope(|scope| {
+                        let ui = scope.create_any_userdata_ref_mut(ui)?;
+                        let result: Result<MultiValue> =
+                            add_contents.call((ui, rows.start, rows.end));
+                        result
+                    })
+                });
+                scroll_area_output_to_lua(lua, output)
+            },
+        );
+    })?;
+    let scroll_area = lua.create_table()?;
+    scroll_area.set(
+        "vertical",
+        lua.create_function(|lua, ()| lua.create_any_userdata(Some(ScrollArea::vertical())))?,
+    )?;
+    scroll_area.set(
+        "horizontal",
+        lua.create_function(|lua, ()| lua.create_any_userdata(Some(ScrollArea::horizontal())))?,
+    )?;
+    scroll_area.set(
+        "both",
+        lua.create_function(|lua, ()| lua.create_any_userdata(Some(ScrollArea::both())))?,
+    )?;
+    scroll_area.set(
+        "neither",
+        lua.create_function(|lua, ()| lua.create_any_userdata(Some(ScrollArea::neither())))?,
+    )?;
+    egui_table.set("scroll_area", scroll_area)?;
+    Ok(())
 }
-impl LuaHelperTrait for WidgetText {
-    fn from_lua(value: Value) -> Result<Self> {
-        match value {
-            Value::String(s) => Ok(s.to_str().unwrap_or_default().into()),
-            Value::UserData(u) => {
-                if let Ok(u) = u.borrow::<WidgetText>() {
-                    Ok(u.clone())
-                } else if let Ok(u) = u.borrow::<RichText>() {
-                    Ok(u.clone().into())
-                } else {
-                    return Err(mlua::Error::FromLuaConversionError {
-                        from: "userdata",
-                        to: "widgettext",
-                        message: None,
-                    });
-                }
+fn add_style(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|style: &mut UserDataRegistry<Style>| {
+        // these return copies, so edit the copy and assign it back: `style.spacing = spacing`
+        style.add_field_method_get("spacing", |lua, this| {
+            lua.create_any_userdata(this.spacing.clone())
+        });
+        style.add_field_method_get("interaction", |lua, this| {
+            lua.create_any_userdata(this.interaction.clone())
+        });
+        style.add_field_method_get("visuals", |lua, this| {
+            lua.create_any_userdata(this.visuals.clone())
+        });
+        style.add_field_method_get("text_styles", |lua, this| {
+            let text_styles = lua.create_table()?;
+            for (text_style, font_id) in this.text_styles.iter() {
+                text_styles.set(
+                    TextStyle::to_lua(text_style.clone(), lua)?,
+                    FontId::to_lua(font_id.clone(), lua)?,
+                )?;
             }
-            _ => Err(mlua::Error::FromLuaConversionError {
-                from: "luavalue",
-                to: "widgettext",
-                message: None,
-            }),
-        }
-    }
-
-    fn to_lua(value: Self, lua: &Lua) -> Result<Value> {
-        Ok(mlua::Value::UserData(lua.create_any_userdata(value)?))
-    }
-
-    fn add_to_lua(lua: &Lua, _egui_table: &Table) -> Result<()> {
-        lua.register_userdata_type(|_reg: &mut UserDataRegistry<WidgetText>| {})?;
-        Ok(())
-    }
-}
+            Ok(text_styles)
+        });
+        style.add_field_method_get("animation_time", |_, this| Ok(this.animation_time));
+        style.add_field_method_get("explanation_tooltips", |_, this| {
+            Ok(this.explanation_tooltips)
+        });
 
-impl LuaHelperTrait for RichText {
-    fn from_lua(value: Value) -> Result<Self> {
-        match value {
-            Value::String(s) => Ok(s.to_str().unwrap_or_default().into()),
-            Value::UserData(u) => {
-                if let Ok(u) = u.borrow::<RichText>() {
-                    Ok(u.clone())
-                } else {
-                    Err(mlua::Error::FromLuaConversionError {
-                        from: "userdata",
-                        to: "widgettext",
-                        message: None,
-                    })
+        style.add_field_method_set("spacing", |_, this, value: UserDataRef<Spacing>| {
+            this.spacing = value.clone();
+            Ok(())
+        });
+        style.add_field_method_set("interaction", |_, this, value: UserDataRef<Interaction>| {
+            this.interaction = value.clone();
+            Ok(())
+        });
+        style.add_field_method_set("visuals", |_, this, value: UserDataRef<Visuals>| {
+            this.visuals = value.clone();
+            Ok(())
+        });
+        // egui looks up the built-in styles by themselves, so they can't be left out.
+        style.add_field_method_set("text_styles", |lua, this, value: Table| {
+            let text_styles: BTreeMap<TextStyle, FontId> = value
+                .pairs::<Value, Value>()
+                .map(|pair| {
+                    let (text_style, font_id) = pair?;
+                    Ok((TextStyle::from_lua(text_style)?, FontId::from_lua(font_id)?))
+                })
+                .collect::<Result<_>>()?;
+            for text_style in [
+                TextStyle::Small,
+                TextStyle::Body,
+                TextStyle::Monospace,
+                TextStyle::Button,
+                TextStyle::Heading,
+            ] {
+                if !text_styles.contains_key(&text_style) {
+                    return Err(mlua::Error::RuntimeError(format!(
+                        "text_styles is missing the built-in style {text_style}"
+                    )));
                 }
             }
-            Value::Table(_t) => {
-                Err(mlua::Error::FromLuaConversionError {
-                    from: "table",
-                    to: "widgettext",
-                    message: None,
+            with_known_fonts(lua, |known_fonts| {
+                text_styles.values().try_for_each(|font_id| {
+                    check_font_family(&known_fonts.families, &font_id.family)
                 })
-                // if let Ok(text) =  t.get::<_, String>("text")  {
-                //     todo!()
-                // } else {
-                //     return e;
-                // }
-            }
-            _ => Err(mlua::Error::FromLuaConversionError {
-                from: "luavalue",
-                to: "widgettext",
-                message: None,
-            }),
-        }
-    }
-
-    fn to_lua(value: Self, lua: &Lua) -> Result<Value> {
-        Ok(Value::UserData(lua.create_any_userdata(value)?))
-    }
-
-    fn add_to_lua(lua: &Lua, egui_table: &Table) -> Result<()> {
-        lua.register_userdata_type(|reg: &mut UserDataRegistry<RichText>| {
-            reg.add_method("is_empty", |_, this, ()| Ok(this.is_empty()));
-            reg.add_method("text", |_, this, ()| Ok(this.text().to_string()));
-            reg.add_method("size", |lua, this, size: f32| {
-                lua.create_any_userdata(this.clone().size(size))
-            });
-        })?;
-        let rich_text = lua.create_table()?;
-        rich_text.set(
-            "new",
-            lua.create_function(|lua, text: String| lua.create_any_userdata(RichText::new(text)))?,
-        )?;
-
-        egui_table.set("rich_text", rich_text)
-    }
-}
+            })?;
+            this.text_styles = text_styles;
+            Ok(())
+        });
+        style.add_field_method_set("animation_time", |_, this, value: f32| {
+            this.animation_time = value;
+            Ok(())
+        });
+        style.add_field_method_set("explanation_tooltips", |_, this, value: bool| {
+            this.explanation_tooltips = value;
+            Ok(())
+        });
 
-#[derive(Hash, Debug)]
-enum LuaHashable<'lua> {
-    LuaString(mlua::String<'lua>),
-    Integer(i32),
-}
-impl<'lua> LuaHashable<'lua> {
-    fn from_lua(value: Value<'lua>) -> Result<Self> {
-        match value {
-            Value::Integer(i) => Ok(Self::Integer(i)),
-            Value::String(i) => Ok(Self::LuaString(i)),
-            _ => Err(mlua::Error::FromLuaConversionError {
-                from: "value",
-                to: "LuaHashable",
-                message: None,
-            }),
-        }
-    }
-}
-fn add_style(lua: &Lua, egui_table: &Table) -> Result<()> {
-    lua.register_userdata_type(|style: &mut UserDataRegistry<Style>| {
         style.add_method_mut("ui", |_, this, mut ui: UserDataRefMut<Ui>| {
             this.ui(&mut ui);
             Ok(())
         });
     })?;
+    lua.register_userdata_type(|interaction: &mut UserDataRegistry<Interaction>| {
+        interaction.add_field_method_get("resize_grab_radius_side", |_, this| {
+            Ok(this.resize_grab_radius_side)
+        });
+        interaction.add_field_method_get("resize_grab_radius_corner", |_, this| {
+            Ok(this.resize_grab_radius_corner)
+        });
+        interaction.add_field_method_get("show_tooltips_only_when_still", |_, this| {
+            Ok(this.show_tooltips_only_when_still)
+        });
+        interaction.add_field_method_get("tooltip_delay", |_, this| Ok(this.tooltip_delay));
+        interaction.add_field_method_get("selectable_labels", |_, this| Ok(this.selectable_labels));
+        interaction.add_field_method_get("multi_widget_text_select", |_, this| {
+            Ok(this.multi_widget_text_select)
+        });
+
+        interaction.add_field_method_set("resize_grab_radius_side", |_, this, value: f32| {
+            this.resize_grab_radius_side = value;
+            Ok(())
+        });
+        interaction.add_field_method_set("resize_grab_radius_corner", |_, this, value: f32| {
+            this.resize_grab_radius_corner = value;
+            Ok(())
+        });
+        interaction.add_field_method_set(
+            "show_tooltips_only_when_still",
+            |_, this, value: bool| {
+                this.show_tooltips_only_when_still = value;
+                Ok(())
+            },
+        );
+        interaction.add_field_method_set("tooltip_delay", |_, this, value: f32| {
+            this.tooltip_delay = value;
+            Ok(())
+        });
+        interaction.add_field_method_set("selectable_labels", |_, this, value: bool| {
+            this.selectable_labels = value;
+            Ok(())
+        });
+        interaction.add_field_method_set("multi_widget_text_select", |_, this, value: bool| {
+            this.multi_widget_text_select = value;
+            Ok(())
+        });
+    })?;
     let style = lua.create_table()?;
     style.set(
         "default",
@@ -1702,6 +6233,193 @@ fn add_style(lua: &Lua, egui_table: &Table) -> Result<()> {
     egui_table.set("style", style)?;
     Ok(())
 }
+fn add_visuals(lua: &Lua, egui_table: &Table) -> Result<()> {
+    lua.register_userdata_type(|visuals: &mut UserDataRegistry<Visuals>| {
+        visuals.add_field_method_get("dark_mode", |_, this| Ok(this.dark_mode));
+        visuals.add_field_method_get("override_text_color", |lua, this| {
+            this.override_text_color
+                .map(|c| Color32::to_lua(c, lua))
+                .transpose()
+        });
+        // returns a copy, assign it back with `visuals.widgets = widgets` after editing
+        visuals.add_field_method_get("widgets", |lua, this| {
+            lua.create_any_userdata(this.widgets.clone())
+        });
+        visuals.add_field_method_get("selection", |lua, this| {
+            let selection = lua.create_table()?;
+            selection.set("bg_fill", Color32::to_lua(this.selection.bg_fill, lua)?)?;
+            selection.set("stroke", Stroke::to_lua(this.selection.stroke, lua)?)?;
+            Ok(selection)
+        });
+        visuals.add_field_method_get("hyperlink_color", |lua, this| {
+            Color32::to_lua(this.hyperlink_color, lua)
+        });
+        visuals.add_field_method_get("faint_bg_color", |lua, this| {
+            Color32::to_lua(this.faint_bg_color, lua)
+        });
+        visuals.add_field_method_get("extreme_bg_color", |lua, this| {
+            Color32::to_lua(this.extreme_bg_color, lua)
+        });
+        visuals.add_field_method_get("code_bg_color", |lua, this| {
+            Color32::to_lua(this.code_bg_color, lua)
+        });
+        visuals.add_field_method_get("warn_fg_color", |lua, this| {
+            Color32::to_lua(this.warn_fg_color, lua)
+        });
+        visuals.add_field_method_get("error_fg_color", |lua, this| {
+            Color32::to_lua(this.error_fg_color, lua)
+        });
+        visuals.add_field_method_get("window_rounding", |lua, this| {
+            Rounding::to_lua(this.window_rounding, lua)
+        });
+        visuals.add_field_method_get("window_shadow", |lua, this| {
+            lua.create_any_userdata(this.window_shadow)
+        });
+        visuals.add_field_method_get("window_fill", |lua, this| {
+            Color32::to_lua(this.window_fill, lua)
+        });
+        visuals.add_field_method_get("window_stroke", |lua, this| {
+            Stroke::to_lua(this.window_stroke, lua)
+        });
+        visuals.add_field_method_get("menu_rounding", |lua, this| {
+            Rounding::to_lua(this.menu_rounding, lua)
+        });
+        visuals.add_field_method_get("panel_fill", |lua, this| {
+            Color32::to_lua(this.panel_fill, lua)
+        });
+        visuals.add_field_method_get("popup_shadow", |lua, this| {
+            lua.create_any_userdata(this.popup_shadow)
+        });
+        visuals.add_field_method_get("striped", |_, this| Ok(this.striped));
+
+        visuals.add_field_method_set("dark_mode", |_, this, value: bool| {
+            this.dark_mode = value;
+            Ok(())
+        });
+        visuals.add_field_method_set("override_text_color", |_, this, value: Value| {
+            this.override_text_color = if value.is_nil() {
+                None
+            } else {
+                Some(Color32::from_lua(value)?)
+            };
+            Ok(())
+        });
+        visuals.add_field_method_set("widgets", |_, this, value: UserDataRef<Widgets>| {
+            this.widgets = value.clone();
+            Ok(())
+        });
+        visuals.add_field_method_set("selection", |_, this, value: Table| {
+            this.selection = Selection {
+                bg_fill: Color32::from_lua(value.get("bg_fill")?)?,
+                stroke: Stroke::from_lua(value.get("stroke")?)?,
+            };
+            Ok(())
+        });
+        visuals.add_field_method_set("hyperlink_color", |_, this, value: Value| {
+            this.hyperlink_color = Color32::from_lua(value)?;
+            Ok(())
+        });
+        visuals.add_field_method_set("faint_bg_color", |_, this, value: Value| {
+            this.faint_bg_color = Color32::from_lua(value)?;
+            Ok(())
+        });
+        visuals.add_field_method_set("extreme_bg_color", |_, this, value: Value| {
+            this.extreme_bg_color = Color32::from_lua(value)?;
+            Ok(())
+        });
+        visuals.add_field_method_set("code_bg_color", |_, this, value: Value| {
+            this.code_bg_color = Color32::from_lua(value)?;
+            Ok(())
+        });
+        visuals.add_field_method_set("warn_fg_color", |_, this, value: Value| {
+            this.warn_fg_color = Color32::from_lua(value)?;
+            Ok(())
+        });
+        visuals.add_field_method_set("error_fg_color", |_, this, value: Value| {
+            this.error_fg_color = Color32::from_lua(value)?;
+            Ok(())
+        });
+        visuals.add_field_method_set("window_rounding", |_, this, value: Value| {
+            this.window_rounding = Rounding::from_lua(value)?;
+            Ok(())
+        });
+        visuals.add_field_method_set("window_shadow", |_, this, value: UserDataRef<Shadow>| {
+            this.window_shadow = *value;
+            Ok(())
+        });
+        visuals.add_field_method_set("window_fill", |_, this, value: Value| {
+            this.window_fill = Color32::from_lua(value)?;
+            Ok(())
+        });
+        visuals.add_field_method_set("window_stroke", |_, this, value: Value| {
+            this.window_stroke = Stroke::from_lua(value)?;
+            Ok(())
+        });
+        visuals.add_field_method_set("menu_rounding", |_, this, value: Value| {
+            this.menu_rounding = Rounding::from_lua(value)?;
+            Ok(())
+        });
+        visuals.add_field_method_set("panel_fill", |_, this, value: Value| {
+            this.panel_fill = Color32::from_lua(value)?;
+            Ok(())
+        });
+        visuals.add_field_method_set("popup_shadow", |_, this, value: UserDataRef<Shadow>| {
+            this.popup_shadow = *value;
+            Ok(())
+        });
+        visuals.add_field_method_set("striped", |_, this, value: bool| {
+            this.striped = value;
+            Ok(())
+        });
+    })?;
+    lua.register_userdata_type(|widgets: &mut UserDataRegistry<Widgets>| {
+        // these return copies, assign them back with `widgets.hovered = hovered` after editing
+        widgets.add_field_method_get("noninteractive", |lua, this| {
+            lua.create_any_userdata(this.noninteractive)
+        });
+        widgets.add_field_method_get("inactive", |lua, this| {
+            lua.create_any_userdata(this.inactive)
+        });
+        widgets.add_field_method_get("hovered", |lua, this| lua.create_any_userdata(this.hovered));
+        widgets.add_field_method_get("active", |lua, this| lua.create_any_userdata(this.active));
+        widgets.add_field_method_get("open", |lua, this| lua.create_any_userdata(this.open));
+
+        widgets.add_field_method_set(
+            "noninteractive",
+            |_, this, value: UserDataRef<WidgetVisuals>| {
+                this.noninteractive = *value;
+                Ok(())
+            },
+        );
+        widgets.add_field_method_set("inactive", |_, this, value: UserDataRef<WidgetVisuals>| {
+            this.inactive = *value;
+            Ok(())
+        });
+        widgets.add_field_method_set("hovered", |_, this, value: UserDataRef<WidgetVisuals>| {
+            this.hovered = *value;
+            Ok(())
+        });
+        widgets.add_field_method_set("active", |_, this, value: UserDataRef<WidgetVisuals>| {
+            this.active = *value;
+            Ok(())
+        });
+        widgets.add_field_method_set("open", |_, this, value: UserDataRef<WidgetVisuals>| {
+            this.open = *value;
+            Ok(())
+        });
+    })?;
+    let visuals = lua.create_table()?;
+    visuals.set(
+        "dark",
+        lua.create_function(|lua, _: ()| lua.create_any_userdata(Visuals::dark()))?,
+    )?;
+    visuals.set(
+        "light",
+        lua.create_function(|lua, _: ()| lua.create_any_userdata(Visuals::light()))?,
+    )?;
+    egui_table.set("visuals", visuals)?;
+    Ok(())
+}
 fn add_frame(lua: &Lua, egui_table: &Table) -> Result<()> {
     let frame = lua.create_table()?;
     frame.set(
@@ -1950,6 +6668,7 @@ fn add_area(lua: &Lua, egui_table: &Table) -> Result<()> {
             |lua, this, (ctx, add_contents): (UserDataRef<Context>, Function)| {
                 let area = *this;
                 let ctx = ctx.clone();
+                remember_fonts(lua, &ctx);
                 area.show(&ctx, |ui| {
                     lua.scope(|scope| {
                         let ui = scope.create_any_userdata_ref_mut(ui)?;
@@ -2191,6 +6910,7 @@ fn add_window(lua: &Lua, egui_table: &Table) -> Result<()> {
             "show",
             |lua, this, (ctx, add_contents, open_table): (UserDataRef<Context>, Function, Option<Table>)| {
                 let ctx = ctx.clone();
+                remember_fonts(lua, &ctx);
                 let mut window = this.take()
                     .ok_or_else(|| mlua::Error::RuntimeError("window is null".to_owned()))?;
                 let mut open = true;
@@ -2247,7 +6967,7 @@ fn add_window(lua: &Lua, egui_table: &Table) -> Result<()> {
     window.set(
         "new",
         lua.create_function(|lua, title: Value| {
-            let w = Window::<'static>::new(WidgetText::from_lua(title)?);
+            let w = Window::<'static>::new(widget_text_from_lua(lua, title)?);
             lua.create_any_userdata(Some(w))
         })?,
     )?;
@@ -2272,6 +6992,7 @@ fn add_central_panel(lua: &Lua, egui_table: &Table) -> Result<()> {
                 "show",
                 |lua, this, (ctx, add_contents): (UserDataRef<Context>, Function)| {
                     let ctx = ctx.clone();
+                    remember_fonts(lua, &ctx);
                     let central_panel = this.take().ok_or_else(|| {
                         mlua::Error::RuntimeError("central panel is null".to_owned())
                     })?;
@@ -2384,6 +7105,7 @@ fn add_side_panel(lua: &Lua, egui_table: &Table) -> Result<()> {
             "show",
             |lua, this, (ctx, add_contents): (UserDataRef<Context>, Function)| {
                 let ctx = ctx.clone();
+                remember_fonts(lua, &ctx);
                 let side_panel = this
                     .take()
                     .ok_or_else(|| mlua::Error::RuntimeError("central panel is null".to_owned()))?;
@@ -2547,6 +7269,7 @@ fn add_top_bottom_panel(lua: &Lua, egui_table: &Table) -> Result<()> {
                 "show",
                 |lua, this, (ctx, add_contents): (UserDataRef<Context>, Function)| {
                     let ctx = ctx.clone();
+                    remember_fonts(lua, &ctx);
                     let side_panel = this.take().ok_or_else(|| {
                         mlua::Error::RuntimeError("top_bottom_panel is null".to_owned())
                     })?;